@@ -0,0 +1,234 @@
+mod resolve;
+pub use resolve::*;
+
+mod validate;
+pub use validate::*;
+
+use std::{collections::BTreeMap, path::PathBuf, process::ExitCode};
+
+use serde::Serialize;
+
+use crate::{input, input::ConfigOptions, runtime, RunOptions};
+
+pub fn config_request(
+    request: ConfigOptions,
+    manifest: Option<&PathBuf>,
+    allow_invalid_manifest: bool,
+) -> ExitCode {
+    match request {
+        ConfigOptions::Validate(build_options) => {
+            let mut config = crate::load_user_config();
+            match input::Manifest::find_manifest(manifest, false) {
+                Ok(manifests) => manifests.apply_on(&mut config),
+                Err(e) => {
+                    println!("{}: {}", console::style("adam error").bright().red(), e);
+                    return ExitCode::FAILURE;
+                }
+            }
+
+            let Some(env) = crate::load_env_options() else {
+                return ExitCode::FAILURE;
+            };
+
+            let problems = validate(&config, &env, &build_options);
+            for problem in problems.iter() {
+                println!(
+                    "{}: {}: {}",
+                    console::style("adam error").bright().red(),
+                    console::style(&problem.scope).bold(),
+                    problem.message
+                );
+            }
+
+            if problems.is_empty() {
+                println!(
+                    "{}: config is valid",
+                    console::style("success").green().bright()
+                );
+                ExitCode::SUCCESS
+            } else {
+                println!("\n{} problem(s) found", problems.len());
+                ExitCode::FAILURE
+            }
+        }
+        ConfigOptions::Show {
+            json,
+            build_options,
+        } => {
+            let Some(resolved) =
+                resolve_options(manifest, allow_invalid_manifest, Some(&build_options))
+            else {
+                return ExitCode::FAILURE;
+            };
+
+            let mut from_cli = resolved.options().clone();
+            build_options.write_to_options(&mut from_cli);
+
+            let mut discovered = from_cli.clone();
+            runtime::resolve_current_project(&mut discovered);
+
+            let mut layers: Vec<_> = resolved
+                .layers
+                .iter()
+                .map(|(source, options)| (*source, options))
+                .collect();
+            layers.push((Source::CliFlag, &from_cli));
+            layers.push((Source::Discovered, &discovered));
+            let fields = label_sources(&layers);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&fields).unwrap());
+            } else {
+                let width = fields.keys().map(|v| v.len()).max().unwrap_or_default();
+                for (name, field) in fields.iter() {
+                    println!(
+                        "{:width$} = {} {}",
+                        name,
+                        field.value,
+                        console::style(format!("({})", field.source)).dim(),
+                        width = width
+                    );
+                }
+            }
+
+            ExitCode::SUCCESS
+        }
+        ConfigOptions::Schema => {
+            let schema = schemars::schema_for!(input::Manifest);
+            println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Where the value of an option came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Default,
+    UserConfig,
+    ProjectManifest,
+    /// The developer's `.adam.local.toml`.
+    LocalManifest,
+    Profile,
+    /// A `[configuration.<Name>]` table for the configuration being built.
+    ConfigurationOverride,
+    /// An `ADAM_*` environment variable.
+    EnvVar,
+    CliFlag,
+    /// Found by adam while building, such as an installed runtime.
+    Discovered,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let w = match self {
+            Source::Default => "default",
+            Source::UserConfig => "user config",
+            Source::ProjectManifest => "project manifest",
+            Source::LocalManifest => "local manifest",
+            Source::Profile => "profile",
+            Source::ConfigurationOverride => "configuration override",
+            Source::EnvVar => "env var",
+            Source::CliFlag => "cli flag",
+            Source::Discovered => "discovered",
+        };
+
+        f.pad(w)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LabeledField {
+    pub value: serde_json::Value,
+    pub source: Source,
+}
+
+/// Labels every field of the last layer with the layer which last changed it. Each layer
+/// must be the previous one with a single source applied on top.
+pub fn label_sources(layers: &[(Source, &RunOptions)]) -> BTreeMap<String, LabeledField> {
+    let snapshots: Vec<_> = layers
+        .iter()
+        .map(|(source, options)| (*source, flatten(options)))
+        .collect();
+
+    let mut output = BTreeMap::new();
+    let Some((_, last)) = snapshots.last() else {
+        return output;
+    };
+
+    for (name, value) in last.iter() {
+        let mut source = Source::Default;
+        for window in snapshots.windows(2) {
+            let (_, previous) = &window[0];
+            let (layer_source, current) = &window[1];
+
+            if previous.get(name) != current.get(name) {
+                source = *layer_source;
+            }
+        }
+
+        output.insert(
+            name.clone(),
+            LabeledField {
+                value: value.clone(),
+                source,
+            },
+        );
+    }
+
+    output
+}
+
+/// Flattens the options into `task.config` style keys.
+fn flatten(options: &RunOptions) -> BTreeMap<String, serde_json::Value> {
+    let serde_json::Value::Object(sections) = serde_json::to_value(options).unwrap() else {
+        unreachable!("run options always serialize to a map");
+    };
+
+    let mut output = BTreeMap::new();
+    for (section, value) in sections {
+        match value {
+            serde_json::Value::Object(fields) => {
+                for (field, value) in fields {
+                    output.insert(format!("{}.{}", section, field), value);
+                }
+            }
+            value => {
+                output.insert(section, value);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_the_last_layer_to_change_a_field() {
+        let defaults = crate::default_run_options();
+
+        let mut from_user = defaults.clone();
+        from_user.task.config = "Release".to_owned();
+        from_user.task.verbosity = 1;
+
+        let mut from_project = from_user.clone();
+        from_project.task.config = "Debug".to_owned();
+
+        let fields = label_sources(&[
+            (Source::Default, &defaults),
+            (Source::UserConfig, &from_user),
+            (Source::ProjectManifest, &from_project),
+        ]);
+
+        assert_eq!(fields["task.config"].source, Source::ProjectManifest);
+        assert_eq!(fields["task.config"].value, "Debug");
+        assert_eq!(fields["task.verbosity"].source, Source::UserConfig);
+        assert_eq!(fields["task.yyc"].source, Source::Default);
+        assert_eq!(fields["no_compile"].value, serde_json::Value::Null);
+    }
+}
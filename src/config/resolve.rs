@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+use camino::Utf8PathBuf;
+
+use super::Source;
+use crate::{input, RunOptions, TaskOptions};
+
+/// The options after each layer of config is applied, from the defaults up to the `ADAM_*`
+/// environment variables. The CLI goes on afterwards, since each command writes its own flags.
+#[derive(Debug, Clone)]
+pub struct ResolvedOptions {
+    /// Each layer is the one before it with a single source applied on top.
+    pub layers: Vec<(Source, RunOptions)>,
+    /// The check script, which comes from the manifest but isn't an option.
+    pub script_path: Option<Utf8PathBuf>,
+}
+
+impl ResolvedOptions {
+    /// The options after every layer.
+    pub fn options(&self) -> &RunOptions {
+        &self.layers.last().unwrap().1
+    }
+
+    pub fn into_parts(mut self) -> (RunOptions, Option<Utf8PathBuf>) {
+        let (_, options) = self.layers.pop().unwrap();
+
+        (options, self.script_path)
+    }
+}
+
+/// Loads the user config, the project and local manifests, the selected profile, the overrides for
+/// the selected configuration, and the environment, and writes each of them onto the default
+/// options in turn. The CLI options are only used to pick the profile and configuration.
+///
+/// If the manifest is invalid, or the profile or configuration can't be resolved, we print why
+/// and return `None`.
+pub fn resolve_options(
+    manifest: Option<&PathBuf>,
+    allow_invalid_manifest: bool,
+    cli_options: Option<&input::BuildOptions>,
+) -> Option<ResolvedOptions> {
+    let defaults = crate::default_run_options();
+    let mut layers = vec![(Source::Default, defaults.clone())];
+    let mut config = crate::load_user_config();
+
+    // every layer is written from scratch, just like the final options
+    let mut push_layer = |source: Source, config: &input::Manifest| {
+        let mut options = defaults.clone();
+        config.clone().write_to_options(&mut options, &mut None);
+        layers.push((source, options));
+    };
+    push_layer(Source::UserConfig, &config);
+
+    let manifests = match input::Manifest::find_manifest(manifest, allow_invalid_manifest) {
+        Ok(v) => v,
+        Err(e) => {
+            adam_error!("invalid manifest: {}", e);
+            return None;
+        }
+    };
+    if let Some(project_manifest) = manifests.project {
+        project_manifest.apply_on(&mut config);
+    }
+    push_layer(Source::ProjectManifest, &config);
+    if let Some(local_manifest) = manifests.local {
+        local_manifest.apply_on(&mut config);
+    }
+    push_layer(Source::LocalManifest, &config);
+
+    let env = crate::load_env_options()?;
+
+    let profile = cli_options
+        .and_then(|v| v.profile.as_deref())
+        .or(env.build_options.profile.as_deref());
+    if let Some(profile) = profile {
+        match config.resolve_profile(profile) {
+            Ok(profile) => profile.apply_on(&mut config),
+            Err(e) => {
+                adam_error!("{}", e);
+                return None;
+            }
+        }
+    }
+    push_layer(Source::Profile, &config);
+
+    // this goes after the profile, since the profile can pick the configuration
+    let configuration = cli_options
+        .and_then(|v| v.config.clone())
+        .or_else(|| env.manifest.configuration.name.clone())
+        .or_else(|| config.configuration.name.clone())
+        .unwrap_or_else(|| TaskOptions::default().config);
+    match config.resolve_configuration(&configuration) {
+        Ok(Some(overrides)) => overrides.apply_on(&mut config),
+        Ok(None) => {}
+        Err(e) => {
+            adam_error!("{}", e);
+            return None;
+        }
+    }
+    push_layer(Source::ConfigurationOverride, &config);
+
+    // the environment goes over every file, but under the CLI
+    env.manifest.apply_on(&mut config);
+
+    let mut options = defaults;
+    let mut script_path = None;
+    config.write_to_options(&mut options, &mut script_path);
+    env.build_options.write_to_options(&mut options);
+    layers.push((Source::EnvVar, options));
+
+    Some(ResolvedOptions {
+        layers,
+        script_path,
+    })
+}
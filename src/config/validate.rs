@@ -0,0 +1,265 @@
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    igor,
+    input::{BuildOptions, EnvOptions, Manifest},
+    runner, runtime,
+};
+
+const TOP_SCOPE: &str = "manifest";
+const ENV_SCOPE: &str = "env";
+
+/// A mistake in the config, and where it was found, such as `profile.ship`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub scope: String,
+    pub message: String,
+}
+
+/// Checks the config for mistakes which otherwise only show up partway through a build: paths
+/// which do not exist, configurations which are not in the yyp, and missing check scripts.
+pub fn validate(config: &Manifest, env: &EnvOptions, build_options: &BuildOptions) -> Vec<Problem> {
+    let mut options = crate::default_run_options();
+    config.clone().write_to_options(&mut options, &mut None);
+    env.manifest
+        .clone()
+        .write_to_options(&mut options, &mut None);
+    env.build_options.clone().write_to_options(&mut options);
+    build_options.clone().write_to_options(&mut options);
+
+    let runtimes_directory = runtime::runtimes_directory(&options.platform);
+    let yyp_configs = project_configurations(options.task.yyp.as_deref());
+    if yyp_configs.is_none() {
+        println!(
+            "{}: no yyp found, so configuration names were not checked",
+            console::style("adam warning").bright().yellow(),
+        );
+    }
+
+    let mut validator = Validator {
+        runtimes_directory,
+        yyp_configs,
+        problems: vec![],
+    };
+
+    validator.check_manifest(TOP_SCOPE, config);
+
+    for name in config.profile.keys() {
+        if let Err(e) = config.resolve_profile(name) {
+            validator.problem(&nested_scope(TOP_SCOPE, "profile", name), e);
+        }
+    }
+
+    for name in config.configuration.overrides.keys() {
+        if let Err(e) = config.resolve_configuration(name) {
+            validator.problem(&nested_scope(TOP_SCOPE, "configuration", name), e);
+        }
+    }
+
+    validator.check_manifest(ENV_SCOPE, &env.manifest);
+    if let Some(Err(e)) = env
+        .build_options
+        .profile
+        .as_ref()
+        .map(|v| config.resolve_profile(v))
+    {
+        validator.problem(ENV_SCOPE, e);
+    }
+
+    if let Some(name) = &build_options.config {
+        validator.check_configuration_name("cli", name);
+    }
+    for message in runner::check_build_args(
+        build_options.jobs,
+        &build_options.igor_arg,
+        &build_options.asset_compiler_arg,
+    ) {
+        validator.problem("cli", message);
+    }
+
+    validator.problems
+}
+
+struct Validator {
+    runtimes_directory: Option<Utf8PathBuf>,
+    yyp_configs: Option<Vec<String>>,
+    problems: Vec<Problem>,
+}
+
+impl Validator {
+    fn problem(&mut self, scope: &str, message: impl std::fmt::Display) {
+        self.problems.push(Problem {
+            scope: scope.to_owned(),
+            message: message.to_string(),
+        });
+    }
+
+    fn check_manifest(&mut self, scope: &str, manifest: &Manifest) {
+        let paths = [
+            ("gms2_install_location", &manifest.gms2_install_location),
+            (
+                "runtime_location_override",
+                &manifest.runtime_location_override,
+            ),
+            ("visual_studio_path", &manifest.visual_studio_path),
+            ("user_license_folder", &manifest.user_license_folder),
+        ];
+        for (key, path) in paths {
+            if let Some(path) = path.as_ref().filter(|v| v.exists() == false) {
+                self.problem(
+                    scope,
+                    format!("`{}` is `{}`, which does not exist", key, path),
+                );
+            }
+        }
+
+        if let (Some(runtime), Some(runtimes_directory)) =
+            (&manifest.runtime, &self.runtimes_directory)
+        {
+            let path = runtimes_directory.join(format!("runtime-{}", runtime));
+            if path.exists() == false {
+                self.problem(
+                    scope,
+                    format!(
+                        "runtime `{}` is not installed in `{}`. Install it with `adam runtime install {}`",
+                        runtime, runtimes_directory, runtime
+                    ),
+                );
+            }
+        }
+
+        if let Some(script) = &manifest.path_to_run_windows {
+            self.check_script(scope, "path_to_run_windows", script, false);
+        }
+        if let Some(script) = &manifest.path_to_run_nix {
+            self.check_script(scope, "path_to_run_nix", script, cfg!(unix));
+        }
+
+        for message in runner::check_build_args(
+            manifest.jobs,
+            &manifest.igor_args,
+            &manifest.asset_compiler_args,
+        ) {
+            self.problem(scope, message);
+        }
+
+        if let Some(workspace) = &manifest.workspace {
+            for member in workspace.members.iter() {
+                if member.is_dir() == false {
+                    self.problem(
+                        scope,
+                        format!("workspace member `{}` is not a directory", member),
+                    );
+                }
+            }
+        }
+
+        if let Some(name) = &manifest.configuration.name {
+            self.check_configuration_name(scope, name);
+        }
+
+        for (name, overrides) in manifest.configuration.overrides.iter() {
+            let scope = nested_scope(scope, "configuration", name);
+            self.check_configuration_name(&scope, name);
+            self.check_manifest(&scope, overrides);
+        }
+
+        for (name, profile) in manifest.profile.iter() {
+            self.check_manifest(&nested_scope(scope, "profile", name), profile);
+        }
+    }
+
+    fn check_script(
+        &mut self,
+        scope: &str,
+        key: &str,
+        script: &Utf8Path,
+        must_be_executable: bool,
+    ) {
+        if script.is_file() == false {
+            self.problem(
+                scope,
+                format!("`{}` is `{}`, which does not exist", key, script),
+            );
+            return;
+        }
+
+        #[cfg(unix)]
+        if must_be_executable {
+            use std::os::unix::fs::PermissionsExt;
+
+            let executable = script
+                .metadata()
+                .is_ok_and(|v| v.permissions().mode() & 0o111 != 0);
+            if executable == false {
+                self.problem(
+                    scope,
+                    format!(
+                        "`{}` is not executable. Run `chmod +x {}` to fix it",
+                        script, script
+                    ),
+                );
+            }
+        }
+
+        #[cfg(not(unix))]
+        let _ = must_be_executable;
+    }
+
+    fn check_configuration_name(&mut self, scope: &str, name: &str) {
+        let Some(yyp_configs) = &self.yyp_configs else {
+            return;
+        };
+
+        if yyp_configs.iter().any(|v| v == name) == false {
+            self.problem(
+                scope,
+                format!(
+                    "configuration `{}` is not in the yyp. Known configurations are: {}",
+                    name,
+                    yyp_configs.join(", ")
+                ),
+            );
+        }
+    }
+}
+
+/// The scope of a table within another scope, such as `profile.ship.configuration.Release`.
+fn nested_scope(scope: &str, kind: &str, name: &str) -> String {
+    if scope == TOP_SCOPE {
+        format!("{}.{}", kind, name)
+    } else {
+        format!("{}.{}.{}", scope, kind, name)
+    }
+}
+
+/// Reads every configuration name out of the project's yyp.
+pub fn project_configurations(yyp: Option<&str>) -> Option<Vec<String>> {
+    let application_data = igor::ApplicationData::new(yyp).ok()?;
+    let yyp_path = application_data
+        .current_directory
+        .join(application_data.project_name?)
+        .with_extension("yyp");
+
+    let yyp = igor::read_yyp(&yyp_path)?;
+
+    fn walk(config: &serde_json::Value, output: &mut Vec<String>) {
+        if let Some(name) = config.get("name").and_then(|v| v.as_str()) {
+            output.push(name.to_owned());
+        }
+
+        for child in config
+            .get("children")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            walk(child, output);
+        }
+    }
+
+    let mut output = vec![];
+    walk(yyp.get("configs")?, &mut output);
+
+    Some(output)
+}
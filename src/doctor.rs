@@ -0,0 +1,282 @@
+use std::process::ExitCode;
+
+use camino::Utf8Path;
+
+use crate::{gm_artifacts, input, runtime, RunOptions};
+
+/// Checks every path which adam needs to build, printing a fix for each one which is wrong.
+pub fn doctor(mut options: RunOptions) -> ExitCode {
+    let mut report = Report::default();
+
+    let install = &options.platform.gms2_application_location;
+    if install.exists() {
+        report.pass("GameMaker install", install);
+    } else {
+        report.fail(
+            "GameMaker install",
+            format!("`{}` does not exist", install),
+            "install GameMaker, or set `gms2_install_location` to where it is installed. \
+            Steam installs always need this set.",
+        );
+    }
+
+    let selection = runtime::resolve_current_project(&mut options);
+    let runtime_location = &options.platform.runtime_location;
+    if runtime_location.exists() {
+        report.pass(
+            "runtime",
+            format!(
+                "{} at `{}` ({})",
+                selection.version, runtime_location, selection.reason
+            ),
+        );
+
+        report.check_binary("Igor", &gm_artifacts::igor_path(runtime_location));
+
+        // on windows, the macros leave off the `.exe`, so we add it back on
+        let asset_compiler = gm_artifacts::asset_compiler_path(runtime_location);
+        let asset_compiler = if cfg!(target_os = "windows") {
+            asset_compiler.with_extension("exe")
+        } else {
+            asset_compiler
+        };
+        report.check_binary("GMAssetCompiler", &asset_compiler);
+    } else {
+        report.fail(
+            "runtime",
+            format!(
+                "runtime {} does not exist at `{}`",
+                selection.version, runtime_location
+            ),
+            format!(
+                "run `adam runtime install {}`, or set `runtime` to one listed by `adam runtime list`",
+                selection.version
+            ),
+        );
+        report.skip("Igor", "the runtime is missing");
+        report.skip("GMAssetCompiler", "the runtime is missing");
+    }
+
+    let compiler_cache = &options.platform.compiler_cache;
+    let user = match input::read_user_info(compiler_cache) {
+        Ok(user) => {
+            report.pass(
+                "um.json",
+                format!("logged in as {} ({})", user.user_name, user.user_id),
+            );
+            Some(user)
+        }
+        Err(e) if options.task.no_user_folder => {
+            report.skip("um.json", format!("`no_user_folder` is set ({:#})", e));
+            None
+        }
+        Err(e) => {
+            report.fail(
+                "um.json",
+                format!("{:#}", e),
+                "open the GameMaker IDE and log in, so that it writes out `um.json`",
+            );
+            None
+        }
+    };
+
+    let license_folder = if options.platform.user_license_folder.as_str().is_empty() {
+        user.as_ref()
+            .map(|user| compiler_cache.join(user.license_folder_name()))
+    } else {
+        Some(options.platform.user_license_folder.clone())
+    };
+    match &license_folder {
+        Some(license_folder) if license_folder.join("licence.plist").exists() => {
+            report.pass("license folder", license_folder);
+        }
+        Some(license_folder) if license_folder.exists() => {
+            report.fail(
+                "license folder",
+                format!("`{}` has no `licence.plist`", license_folder),
+                "open the GameMaker IDE and log in again to refresh your license",
+            );
+        }
+        Some(license_folder) => {
+            report.fail(
+                "license folder",
+                format!("`{}` does not exist", license_folder),
+                "log in with the GameMaker IDE, or set `user_license_folder` to your license folder",
+            );
+        }
+        None => {
+            report.fail(
+                "license folder",
+                "no license folder was set, and none could be found from `um.json`",
+                "log in with the GameMaker IDE, or set `user_license_folder` to your license folder",
+            );
+        }
+    }
+
+    if cfg!(target_os = "windows") {
+        let visual_studio_path = if options.platform.visual_studio_path.as_str().is_empty() {
+            license_folder
+                .as_deref()
+                .and_then(input::local_settings_visual_studio_path)
+        } else {
+            Some(options.platform.visual_studio_path.clone())
+        };
+
+        match visual_studio_path {
+            Some(path) if path.exists() => {
+                report.pass("visual studio", path);
+            }
+            Some(path) => {
+                report.problem(
+                    options.task.yyc,
+                    "visual studio",
+                    format!("`{}` does not exist", path),
+                    "install the Visual Studio C++ build tools, then set the path to `vcvars64.bat` \
+                    in the IDE's Windows platform settings, or set `visual_studio_path`. This is \
+                    only needed for the YYC",
+                );
+            }
+            None => {
+                report.problem(
+                    options.task.yyc,
+                    "visual studio",
+                    "no visual studio path is set in `local_settings.json`",
+                    "set the path to `vcvars64.bat` in the IDE's Windows platform settings, \
+                    or set `visual_studio_path`. This is only needed for the YYC",
+                );
+            }
+        }
+    } else {
+        report.skip("visual studio", "only needed for the YYC on Windows");
+    }
+
+    let output_folder = &options.task.output_folder;
+    match check_writable(output_folder) {
+        Ok(()) => report.pass("output folder", output_folder),
+        Err(e) => report.fail(
+            "output folder",
+            format!("`{}` is not writable ({})", output_folder, e),
+            "fix the folder's permissions, or set `output_folder` to somewhere adam can write",
+        ),
+    }
+
+    if report.failures == 0 {
+        println!(
+            "\nadam doctor: {}",
+            console::style("no problems found").green().bright()
+        );
+        if report.warnings > 0 {
+            println!(
+                "adam doctor: {}",
+                console::style(format!("{} warning(s)", report.warnings))
+                    .yellow()
+                    .bright()
+            );
+        }
+        ExitCode::SUCCESS
+    } else {
+        println!(
+            "\nadam doctor: {}",
+            console::style(format!("{} problem(s) found", report.failures))
+                .red()
+                .bright()
+        );
+        ExitCode::FAILURE
+    }
+}
+
+/// Writes and removes a scratch file in the folder. If the folder doesn't exist yet, we check the
+/// closest parent which does, since that's where the folder will be made.
+fn check_writable(folder: &Utf8Path) -> std::io::Result<()> {
+    let mut existing = folder;
+    while existing.exists() == false {
+        match existing.parent() {
+            Some(parent) if parent.as_str().is_empty() == false => existing = parent,
+            _ => {
+                existing = Utf8Path::new(".");
+                break;
+            }
+        }
+    }
+
+    let probe = existing.join(".adam-doctor");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    failures: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn pass(&mut self, name: &str, detail: impl std::fmt::Display) {
+        println!(
+            "{:>8} {}: {}",
+            console::style("ok").green().bright(),
+            name,
+            detail
+        );
+    }
+
+    fn fail(&mut self, name: &str, problem: impl std::fmt::Display, fix: impl std::fmt::Display) {
+        self.failures += 1;
+
+        println!(
+            "{:>8} {}: {}",
+            console::style("FAILED").red().bright(),
+            name,
+            problem
+        );
+        println!("{:>8} {}", console::style("fix").bold(), fix);
+    }
+
+    fn warn(&mut self, name: &str, problem: impl std::fmt::Display, fix: impl std::fmt::Display) {
+        self.warnings += 1;
+
+        println!(
+            "{:>8} {}: {}",
+            console::style("warning").yellow().bright(),
+            name,
+            problem
+        );
+        println!("{:>8} {}", console::style("fix").bold(), fix);
+    }
+
+    /// Fails if the check is needed for this build, and warns otherwise.
+    fn problem(
+        &mut self,
+        required: bool,
+        name: &str,
+        problem: impl std::fmt::Display,
+        fix: impl std::fmt::Display,
+    ) {
+        if required {
+            self.fail(name, problem, fix);
+        } else {
+            self.warn(name, problem, fix);
+        }
+    }
+
+    fn skip(&mut self, name: &str, reason: impl std::fmt::Display) {
+        println!(
+            "{:>8} {}: {}",
+            console::style("skipped").dim(),
+            name,
+            reason
+        );
+    }
+
+    fn check_binary(&mut self, name: &str, path: &Utf8Path) {
+        if path.exists() {
+            self.pass(name, path);
+        } else {
+            self.fail(
+                name,
+                format!("`{}` does not exist", path),
+                "the runtime is incomplete. Reinstall it with `adam runtime install <version> --force`",
+            );
+        }
+    }
+}
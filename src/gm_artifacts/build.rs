@@ -60,7 +60,7 @@ impl GmBuild {
             license_dir: build_data.license_folder.clone(),
             runtime_location: build_data.runtime_location.clone(),
             target_options: cache.join("targetoptions.json"),
-            target_mask: build_data.target_mask.to_string(),
+            target_mask: build_data.target.target_mask().to_string(),
             application_path: build_data.application_path.clone(),
             output_folder: build_data.folders.output.clone(),
             config: build_data.config.clone(),
@@ -1,5 +1,5 @@
 use crate::igor::BuildData;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 
 macro_rules! path {
@@ -8,6 +8,37 @@ macro_rules! path {
     }}
 }
 
+/// The path to Igor within the given runtime.
+pub fn igor_path(runtime_location: &Utf8Path) -> Utf8PathBuf {
+    if cfg!(target_os = "windows") {
+        runtime_location.join("bin/igor/windows/x64/Igor.exe")
+    } else if cfg!(target_os = "macos") {
+        if cfg!(target_arch = "aarch64") {
+            runtime_location.join("bin/igor/osx/arm64/Igor")
+        } else {
+            runtime_location.join("bin/igor/osx/x64/Igor")
+        }
+    } else {
+        runtime_location.join("bin/igor/linux/x64/Igor")
+    }
+}
+
+/// The path to the GMAssetCompiler within the given runtime. On Windows, this has no `.exe`, as
+/// that's what Igor expects in the macros.
+pub fn asset_compiler_path(runtime_location: &Utf8Path) -> Utf8PathBuf {
+    if cfg!(target_os = "windows") {
+        runtime_location.join("bin/assetcompiler/windows/x64/GMAssetCompiler")
+    } else if cfg!(target_os = "macos") {
+        if cfg!(target_arch = "aarch64") {
+            runtime_location.join("bin/assetcompiler/osx/arm64/GMAssetCompiler.dll")
+        } else {
+            runtime_location.join("bin/assetcompiler/osx/x64/GMAssetCompiler.dll")
+        }
+    } else {
+        runtime_location.join("bin/assetcompiler/linux/x64/GMAssetCompiler.dll")
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GmMacros {
     #[serde(rename = "Desktop")]
@@ -177,12 +208,8 @@ impl GmMacros {
     #[cfg(target_os = "windows")]
     pub fn new(build_data: &BuildData) -> Self {
         Self {
-            igor_path: build_data
-                .runtime_location
-                .join("bin/igor/windows/x64/Igor.exe"),
-            asset_compiler_path: build_data
-                .runtime_location
-                .join("bin/assetcompiler/windows/x64/GMAssetCompiler"),
+            igor_path: igor_path(&build_data.runtime_location),
+            asset_compiler_path: asset_compiler_path(&build_data.runtime_location),
             favorites: build_data.user_dir.join("Favorites"),
             fonts: path!("C:/Windows/Fonts"),
             templates: path!("C:/Users/jjspi/AppData/Roaming/Microsoft/Windows/Templates"),
@@ -223,31 +250,15 @@ impl GmMacros {
         }
     }
 
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(target_os = "macos")]
     pub fn new(build_data: &BuildData) -> Self {
         let application_data = build_data.user_dir.join(".config");
         let common_app_data = path!("/Users/Shared");
         let system_directory = common_app_data.join("GameMakerStudio2");
 
-        let igor_path = if cfg!(target_arch = "aarch64") {
-            build_data.runtime_location.join("bin/igor/osx/arm64/Igor")
-        } else {
-            build_data.runtime_location.join("bin/igor/osx/x64/Igor")
-        };
-
-        let asset_compiler_path = if cfg!(target_arch = "aarch64") {
-            build_data
-                .runtime_location
-                .join("bin/assetcompiler/osx/arm64/GMAssetCompiler.dll")
-        } else {
-            build_data
-                .runtime_location
-                .join("bin/assetcompiler/osx/x64/GMAssetCompiler.dll")
-        };
-
         Self {
-            igor_path,
-            asset_compiler_path,
+            igor_path: igor_path(&build_data.runtime_location),
+            asset_compiler_path: asset_compiler_path(&build_data.runtime_location),
             favorites: build_data.user_dir.join("Library/Favorites"),
             fonts: build_data.user_dir.join("Library/Fonts"),
             templates: build_data.user_dir.join("Templates"),
@@ -276,6 +287,42 @@ impl GmMacros {
             ..Self::create_internal(build_data)
         }
     }
+    #[cfg(target_os = "linux")]
+    pub fn new(build_data: &BuildData) -> Self {
+        let application_data = build_data.user_dir.join(".config");
+        let local_application_data = build_data.user_dir.join(".local/share");
+        let system_directory = local_application_data.join("GameMakerStudio2");
+
+        Self {
+            igor_path: igor_path(&build_data.runtime_location),
+            asset_compiler_path: asset_compiler_path(&build_data.runtime_location),
+            favorites: build_data.user_dir.join("Favorites"),
+            fonts: path!("/usr/share/fonts"),
+            templates: build_data.user_dir.join("Templates"),
+            application_data: application_data.clone(),
+            local_application_data: local_application_data.clone(),
+            internet_cache: build_data.user_dir.join(".cache"),
+            common_application_data: path!("/usr/share"),
+            program_files: path!("/opt"),
+            common_templates: path!("/usr/share/templates"),
+            temp_path: path!("/tmp"),
+            update_uri: "https://gms.yoyogames.com/update-ubuntu.rss".to_owned(),
+            java_exe_path: path!("bin/java"),
+            adb_exe_path: path!("platform-tools/adb"),
+            keytool_exe_path: path!("bin/keytool"),
+            openssl_exe_path: path!("bin/openssl"),
+            user_skin_path: system_directory.join("Skins"),
+            user_override_directory: system_directory.join("User"),
+            system_directory: system_directory.clone(),
+            system_cache_directory: system_directory.join("Cache"),
+            local_directory: application_data.join("GameMakerStudio2"),
+            local_cache_directory: application_data.join("GameMakerStudio2/Cache"),
+            ide_cache_directory: application_data.join("GameMakerStudio2/Cache/GMS2IDE"),
+
+            ..Self::create_internal(build_data)
+        }
+    }
+
     fn create_internal(build_data: &BuildData) -> Self {
         let BuildData {
             folders,
@@ -284,7 +331,7 @@ impl GmMacros {
             user_dir,
             runtime_location,
             application_path,
-            target_mask: _,
+            target: _,
             license_folder: _,
             output_kind: _,
             config: _,
@@ -388,7 +435,7 @@ impl Default for GmMacros {
             program_dir_name: "GameMakerStudio2".to_owned(),
             program_name: "GameMakerStudio2".to_owned(),
             program_name_pretty: "GameMaker Studio 2".to_owned(),
-            runtime_uri: "https://gms.yoyogames.com/Zeus-Runtime.rss".to_owned(),
+            runtime_uri: super::DEFAULT_RUNTIME_FEED.to_owned(),
             update_uri: "https://gms.yoyogames.com/update-mac.rss".to_owned(),
             release_notes_uri: "https://gms.yoyogames.com/ReleaseNotes.html".to_owned(),
             runtime_release_notes_uri: "https://gms.yoyogames.com/release-notes-runtime.html"
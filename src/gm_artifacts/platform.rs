@@ -4,37 +4,43 @@ use once_cell::sync::Lazy;
 #[cfg(target_os = "windows")]
 pub const PLATFORM_KIND: crate::igor::PlatformKind = crate::igor::PlatformKind::Windows;
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "macos")]
 pub const PLATFORM_KIND: crate::igor::PlatformKind = crate::igor::PlatformKind::Darwin;
 
+#[cfg(target_os = "linux")]
+pub const PLATFORM_KIND: crate::igor::PlatformKind = crate::igor::PlatformKind::Linux;
+
 #[derive(Debug)]
 pub struct DefaultPlatformData {
-    pub stable_runtime_location: &'static str,
-    pub beta_runtime_location: &'static str,
+    pub stable_runtime_location: Lazy<Utf8PathBuf>,
+    pub beta_runtime_location: Lazy<Utf8PathBuf>,
     pub stable_application_path: &'static str,
     pub beta_application_path: &'static str,
 
-    pub target_mask: usize,
     pub stable_cached_data: Lazy<Utf8PathBuf>,
     pub beta_cached_data: Lazy<Utf8PathBuf>,
 }
 pub const DEFAULT_RUNTIME_NAME: &str = "2024.6.0.205";
+pub const DEFAULT_RUNTIME_FEED: &str = "https://gms.yoyogames.com/Zeus-Runtime.rss";
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "macos")]
 pub static DEFAULT_PLATFORM_DATA: DefaultPlatformData = {
     DefaultPlatformData {
-        stable_runtime_location: const_format::concatcp!(
-            "/Users/Shared/GameMakerStudio2/Cache/runtimes/runtime-",
-            DEFAULT_RUNTIME_NAME
-        ),
-        beta_runtime_location: const_format::concatcp!(
-            "/Users/Shared/GameMakerStudio2-Beta/Cache/runtimes/runtime-",
-            DEFAULT_RUNTIME_NAME
-        ),
+        stable_runtime_location: Lazy::new(|| {
+            Utf8PathBuf::from(const_format::concatcp!(
+                "/Users/Shared/GameMakerStudio2/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
+        beta_runtime_location: Lazy::new(|| {
+            Utf8PathBuf::from(const_format::concatcp!(
+                "/Users/Shared/GameMakerStudio2-Beta/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
         stable_application_path: "/Applications/GameMaker.app/Contents",
         beta_application_path: "/Applications/GameMaker Beta.app/Contents",
 
-        target_mask: 2,
         stable_cached_data: Lazy::new(|| home_dir().join("GameMakerStudio2")),
         beta_cached_data: Lazy::new(|| home_dir().join("GameMakerStudio2-Beta")),
     }
@@ -43,23 +49,51 @@ pub static DEFAULT_PLATFORM_DATA: DefaultPlatformData = {
 #[cfg(target_os = "windows")]
 pub static DEFAULT_PLATFORM_DATA: DefaultPlatformData = {
     DefaultPlatformData {
-        stable_runtime_location: const_format::concatcp!(
-            "C:/ProgramData/GameMakerStudio2/Cache/runtimes/runtime-",
-            DEFAULT_RUNTIME_NAME
-        ),
-        beta_runtime_location: const_format::concatcp!(
-            "C:/ProgramData/GameMakerStudio2-Beta/Cache/runtimes/runtime-",
-            DEFAULT_RUNTIME_NAME
-        ),
+        stable_runtime_location: Lazy::new(|| {
+            Utf8PathBuf::from(const_format::concatcp!(
+                "C:/ProgramData/GameMakerStudio2/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
+        beta_runtime_location: Lazy::new(|| {
+            Utf8PathBuf::from(const_format::concatcp!(
+                "C:/ProgramData/GameMakerStudio2-Beta/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
         stable_application_path: "C:/Program Files/GameMaker/GameMaker.exe",
         beta_application_path: "C:/Program Files/GameMaker-Beta/GameMaker-Beta.exe",
 
-        target_mask: 64,
         stable_cached_data: Lazy::new(|| home_dir().join("AppData/Roaming/GameMakerStudio2")),
         beta_cached_data: Lazy::new(|| home_dir().join("AppData/Roaming/GameMakerStudio2-Beta")),
     }
 };
 
+/// On Ubuntu, the IDE keeps its runtimes in `~/.local/share` rather than in a shared
+/// system folder, so unlike the other platforms, these paths are per-user.
+#[cfg(target_os = "linux")]
+pub static DEFAULT_PLATFORM_DATA: DefaultPlatformData = {
+    DefaultPlatformData {
+        stable_runtime_location: Lazy::new(|| {
+            data_dir().join(const_format::concatcp!(
+                "GameMakerStudio2/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
+        beta_runtime_location: Lazy::new(|| {
+            data_dir().join(const_format::concatcp!(
+                "GameMakerStudio2-Beta/Cache/runtimes/runtime-",
+                DEFAULT_RUNTIME_NAME
+            ))
+        }),
+        stable_application_path: "/opt/GameMaker",
+        beta_application_path: "/opt/GameMaker-Beta",
+
+        stable_cached_data: Lazy::new(|| home_dir().join("GameMakerStudio2")),
+        beta_cached_data: Lazy::new(|| home_dir().join("GameMakerStudio2-Beta")),
+    }
+};
+
 fn home_dir() -> Utf8PathBuf {
     if cfg!(target_os = "windows") {
         directories::UserDirs::new()
@@ -77,3 +111,13 @@ fn home_dir() -> Utf8PathBuf {
             .unwrap()
     }
 }
+
+#[cfg(target_os = "linux")]
+fn data_dir() -> Utf8PathBuf {
+    directories::BaseDirs::new()
+        .unwrap()
+        .data_dir()
+        .to_owned()
+        .try_into()
+        .unwrap()
+}
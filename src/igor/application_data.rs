@@ -1,9 +1,14 @@
 use std::env;
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 use crate::AnyResult;
 
+/// GameMaker leaves trailing commas in its json, which serde won't read.
+static TRAILING_COMMA: Lazy<Regex> = Lazy::new(|| Regex::new(r",(\s*)([}\]])").unwrap());
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct ApplicationData {
     pub current_directory: Utf8PathBuf,
@@ -11,28 +16,47 @@ pub struct ApplicationData {
 }
 
 impl ApplicationData {
-    pub fn new() -> AnyResult<Self> {
+    /// Finds the project in the current directory. If `yyp` is given, we select the project
+    /// with that name (with or without the `.yyp` extension); otherwise, there must be at most
+    /// one yyp in the directory.
+    pub fn new(yyp: Option<&str>) -> AnyResult<Self> {
         let current_directory = Utf8PathBuf::from_path_buf(
             env::current_dir().map_err(|_| color_eyre::eyre::anyhow!("cannot read directory"))?,
         )
         .map_err(|_| color_eyre::eyre::anyhow!("current dir isn't utf8"))?;
 
-        let mut project_name = None;
+        let mut yyps = vec![];
 
         for file in current_directory.read_dir()?.flatten() {
             let file = file.path();
 
-            if let Some(ext) = file.extension() {
-                if ext == "yyp" {
-                    let stem = file.file_stem().unwrap().to_string_lossy().to_string();
-                    if project_name.is_some() {
-                        color_eyre::eyre::bail!("multiple yyps discovered",);
-                    }
+            if file.extension().is_some_and(|ext| ext == "yyp") {
+                yyps.push(file.file_stem().unwrap().to_string_lossy().to_string());
+            }
+        }
+        yyps.sort();
 
-                    project_name = Some(stem);
+        let project_name = match yyp {
+            Some(yyp) => {
+                let yyp = yyp.strip_suffix(".yyp").unwrap_or(yyp);
+                match yyps.into_iter().find(|v| v == yyp) {
+                    Some(v) => Some(v),
+                    None => {
+                        color_eyre::eyre::bail!("no yyp named `{}` found", yyp);
+                    }
                 }
             }
-        }
+            None => {
+                if yyps.len() > 1 {
+                    color_eyre::eyre::bail!(
+                        "multiple yyps discovered ({}). Please pick one with `--yyp`",
+                        yyps.join(", ")
+                    );
+                }
+
+                yyps.pop()
+            }
+        };
 
         Ok(Self {
             current_directory,
@@ -40,3 +64,24 @@ impl ApplicationData {
         })
     }
 }
+
+/// Reads a yyp as plain json, for the few fields we need out of it.
+pub fn read_yyp(yyp_path: &Utf8Path) -> Option<serde_json::Value> {
+    let txt = std::fs::read_to_string(yyp_path).ok()?;
+
+    serde_json::from_str(&TRAILING_COMMA.replace_all(&txt, "$1$2")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_commas_are_dropped() {
+        let txt = "{\"a\": [1, 2,],\n  \"b\": {\"c\": \"d\",\n},\n}";
+        let value: serde_json::Value =
+            serde_json::from_str(&TRAILING_COMMA.replace_all(txt, "$1$2")).unwrap();
+
+        assert_eq!(value, serde_json::json!({"a": [1, 2], "b": {"c": "d"}}));
+    }
+}
@@ -26,8 +26,8 @@ pub struct BuildData {
     /// This is the runtime location folder
     pub runtime_location: Utf8PathBuf,
 
-    /// The target mask is nearly meaningless, but it appears to be `64`.
-    pub target_mask: usize,
+    /// The platform we are building for. This may not be the host platform.
+    pub target: PlatformKind,
 
     /// The path to the application exe. On macOS, this is to the `.MacOS` folder
     /// within the application.
@@ -39,7 +39,7 @@ pub struct BuildData {
 
 #[derive(Debug, Clone)]
 pub struct TargetFolders {
-    /// This is the parent folder, such as `PROJECT/target/vm`.
+    /// This is the parent folder, such as `PROJECT/target/windows/vm`.
     pub main: Utf8PathBuf,
     /// This is the folder we dump the important stuff inside.
     pub output: Utf8PathBuf,
@@ -55,11 +55,13 @@ impl TargetFolders {
     pub fn new(
         current_directory: &Utf8Path,
         output_folder: &Utf8Path,
+        target: PlatformKind,
         output_kind: OutputKind,
         project_name: &str,
     ) -> AnyResult<Self> {
         let dir = current_directory
             .join(output_folder)
+            .join(target.to_string())
             .join(output_kind.to_string());
 
         let me = TargetFolders {
@@ -89,8 +91,11 @@ impl TargetFolders {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 pub enum OutputKind {
+    #[default]
     #[serde(rename = "VM")]
     Vm,
     #[allow(dead_code)]
@@ -98,12 +103,6 @@ pub enum OutputKind {
     Yyc,
 }
 
-impl Default for OutputKind {
-    fn default() -> Self {
-        Self::Vm
-    }
-}
-
 impl std::fmt::Display for OutputKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -113,11 +112,44 @@ impl std::fmt::Display for OutputKind {
     }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A platform which Igor can build for. The host platform is [`PLATFORM_KIND`], but any of these
+/// can be selected with `--target`.
+///
+/// [`PLATFORM_KIND`]: crate::gm_artifacts::PLATFORM_KIND
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum PlatformKind {
     Windows,
+    #[serde(rename = "mac", alias = "macos")]
+    #[value(name = "mac", alias = "macos")]
     Darwin,
+    #[serde(alias = "ubuntu")]
+    #[value(alias = "ubuntu")]
+    Linux,
+}
+
+impl PlatformKind {
+    /// The target mask Igor expects in the `build.bff` for this platform.
+    pub fn target_mask(self) -> usize {
+        match self {
+            PlatformKind::Windows => 64,
+            PlatformKind::Darwin => 2,
+            PlatformKind::Linux => 128,
+        }
+    }
 }
 
 impl std::fmt::Display for PlatformKind {
@@ -125,6 +157,7 @@ impl std::fmt::Display for PlatformKind {
         match self {
             PlatformKind::Windows => write!(f, "windows"),
             PlatformKind::Darwin => write!(f, "mac"),
+            PlatformKind::Linux => write!(f, "linux"),
         }
     }
 }
@@ -0,0 +1,380 @@
+use std::{
+    io::{IsTerminal, Write},
+    process::ExitCode,
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::{
+    config,
+    input::{self, InitOptions},
+    runtime, RunOptions,
+};
+
+const MANIFEST_NAME: &str = ".adam.toml";
+const COMMENT_WIDTH: usize = 100;
+const CHECK_SCRIPT_WINDOWS: &str = "scripts/check.ps1";
+const CHECK_SCRIPT_NIX: &str = "scripts/check.sh";
+
+/// Sets up adam for the project in the current directory. Anything not given in the options is
+/// asked for, unless `--yes` was passed or we aren't attached to a terminal.
+pub fn init(request: InitOptions, mut options: RunOptions) -> ExitCode {
+    match init_project(request, &mut options) {
+        Ok(()) => {
+            println!(
+                "{}: adam is set up. Run `adam run` to build, or `adam doctor` to check your setup",
+                console::style("success").green().bright()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("{}: {:#}", console::style("adam error").bright().red(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn init_project(request: InitOptions, options: &mut RunOptions) -> crate::AnyResult<()> {
+    let prompt = Prompt {
+        interactive: request.yes == false && std::io::stdin().is_terminal(),
+    };
+
+    if let Some(existing) = input::MANIFEST_NAMES
+        .iter()
+        .find(|name| Utf8Path::new(name).exists())
+    {
+        if *existing != MANIFEST_NAME {
+            color_eyre::eyre::bail!(
+                "this project already has a manifest at `{}`. Remove it to use `adam init`",
+                existing
+            );
+        }
+        if request.force == false {
+            color_eyre::eyre::bail!(
+                "this project already has a manifest at `{}`. Pass `--force` to replace it",
+                existing
+            );
+        }
+    }
+
+    let yyp = pick_yyp(request.yyp.as_deref(), &prompt)?;
+    println!("found project `{}.yyp`", yyp);
+
+    let configurations = config::project_configurations(Some(&yyp));
+    if let Some(configurations) = &configurations {
+        println!("found configurations: {}", configurations.join(", "));
+    }
+    let configuration = match request.config {
+        Some(v) => v,
+        None => prompt.ask("configuration", "Default")?,
+    };
+    if let Some(configurations) = configurations.filter(|v| v.contains(&configuration) == false) {
+        println!(
+            "{}: configuration `{}` is not in the yyp. Known configurations are: {}",
+            console::style("adam warning").bright().yellow(),
+            configuration,
+            configurations.join(", ")
+        );
+    }
+
+    let output_folder = match request.output_folder {
+        Some(v) => v,
+        None => prompt.ask("output folder", "target")?.into(),
+    };
+
+    let test_success_keyword = match request.test_success_keyword {
+        Some(v) => Some(v),
+        None => Some(prompt.ask("`adam test` success keyword (blank for none)", "")?)
+            .filter(|v| v.is_empty() == false),
+    };
+
+    let check_scripts = request.check_scripts || prompt.confirm("write check scripts?", false)?;
+
+    let yyp_path = Utf8PathBuf::from(format!("{}.yyp", yyp));
+    let selection = runtime::resolve_runtime(&mut options.platform, Some(&yyp_path));
+    let installed: Vec<_> = runtime::runtimes_directory(&options.platform)
+        .map(|v| runtime::installed_runtimes(&v))
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .map(|v| v.version.to_string())
+        .collect();
+    if installed.is_empty() {
+        println!(
+            "{}: no runtimes are installed. Install one with `adam runtime install <VERSION>`",
+            console::style("adam warning").bright().yellow(),
+        );
+    } else {
+        println!(
+            "found runtimes: {}. This project would use {} ({})",
+            installed.join(", "),
+            selection.version,
+            selection.reason
+        );
+    }
+
+    let descriptions = serde_json::to_value(schemars::schema_for!(input::Manifest)).unwrap();
+    let descriptions = &descriptions["properties"];
+    let mut manifest = String::from(
+        "# adam's manifest for this project. Run `adam config schema` for every key, and\n\
+        # `adam config show` for the options a build would use.\n",
+    );
+    push_key(&mut manifest, descriptions, "yyp", &yyp, false);
+    push_key(
+        &mut manifest,
+        descriptions,
+        "configuration",
+        &configuration,
+        false,
+    );
+    push_key(
+        &mut manifest,
+        descriptions,
+        "output_folder",
+        output_folder.as_str(),
+        false,
+    );
+    // we only suggest a runtime, since leaving it unset follows the project as it updates
+    let runtime = if installed.is_empty() {
+        ""
+    } else {
+        &selection.version
+    };
+    push_key(&mut manifest, descriptions, "runtime", runtime, true);
+    push_key(
+        &mut manifest,
+        descriptions,
+        "test_success_keyword",
+        test_success_keyword.as_deref().unwrap_or_default(),
+        test_success_keyword.is_none(),
+    );
+    if check_scripts {
+        push_key(
+            &mut manifest,
+            descriptions,
+            "path_to_run_windows",
+            CHECK_SCRIPT_WINDOWS,
+            false,
+        );
+        push_key(
+            &mut manifest,
+            descriptions,
+            "path_to_run_nix",
+            CHECK_SCRIPT_NIX,
+            false,
+        );
+    }
+
+    std::fs::write(MANIFEST_NAME, manifest)?;
+    println!(
+        "{} `{}`",
+        console::style("wrote").green().bright(),
+        MANIFEST_NAME
+    );
+
+    if update_gitignore(&[
+        format!("/{}/", output_folder.as_str().trim_matches('/')),
+        input::LOCAL_MANIFEST_NAME.to_owned(),
+    ])? {
+        println!(
+            "{} `.gitignore`",
+            console::style("updated").green().bright()
+        );
+    }
+
+    if check_scripts {
+        write_check_script(CHECK_SCRIPT_WINDOWS, CHECK_SCRIPT_WINDOWS_TEMPLATE, false)?;
+        write_check_script(CHECK_SCRIPT_NIX, CHECK_SCRIPT_NIX_TEMPLATE, true)?;
+    }
+
+    Ok(())
+}
+
+/// Finds the yyp to build, asking which one to use if there are several.
+fn pick_yyp(yyp: Option<&str>, prompt: &Prompt) -> crate::AnyResult<String> {
+    let mut yyps: Vec<_> = Utf8Path::new(".")
+        .read_dir_utf8()?
+        .flatten()
+        .filter(|entry| entry.path().extension() == Some("yyp"))
+        .filter_map(|entry| entry.path().file_stem().map(|v| v.to_owned()))
+        .collect();
+    yyps.sort();
+
+    if let Some(yyp) = yyp {
+        let yyp = yyp.strip_suffix(".yyp").unwrap_or(yyp);
+        if yyps.iter().any(|v| v == yyp) == false {
+            color_eyre::eyre::bail!("no yyp named `{}` found", yyp);
+        }
+
+        return Ok(yyp.to_owned());
+    }
+
+    match yyps.as_slice() {
+        [] => color_eyre::eyre::bail!(
+            "no yyp found in the current directory. Run `adam init` in your project's folder"
+        ),
+        [yyp] => Ok(yyp.clone()),
+        _ if prompt.interactive => loop {
+            println!("found several yyps: {}", yyps.join(", "));
+            let yyp = prompt.ask("yyp", &yyps[0])?;
+            if yyps.contains(&yyp) {
+                break Ok(yyp);
+            }
+        },
+        _ => color_eyre::eyre::bail!(
+            "multiple yyps discovered ({}). Please pick one with `--yyp`",
+            yyps.join(", ")
+        ),
+    }
+}
+
+/// Writes a key, with its description from the manifest schema above it as a comment. Commented
+/// out keys are still written, so that users can find them later.
+fn push_key(
+    manifest: &mut String,
+    descriptions: &serde_json::Value,
+    key: &str,
+    value: &str,
+    commented: bool,
+) {
+    manifest.push('\n');
+    if let Some(description) = descriptions[key]["description"].as_str() {
+        for line in description.lines() {
+            // schemars joins each paragraph onto one line, so we wrap them again
+            let mut comment = String::from("#");
+            for word in line.split_whitespace() {
+                if comment.len() + word.len() >= COMMENT_WIDTH {
+                    manifest.push_str(&comment);
+                    manifest.push('\n');
+                    comment = String::from("#");
+                }
+                comment.push(' ');
+                comment.push_str(word);
+            }
+            manifest.push_str(&comment);
+            manifest.push('\n');
+        }
+    }
+
+    if commented {
+        manifest.push_str("# ");
+    }
+    manifest.push_str(&format!(
+        "{} = {}\n",
+        key,
+        toml::Value::String(value.to_owned())
+    ));
+}
+
+/// Adds each entry to the `.gitignore`, unless it's already there. Returns true if the file was
+/// changed.
+fn update_gitignore(entries: &[String]) -> crate::AnyResult<bool> {
+    let normalize = |v: &str| v.trim().trim_matches('/').to_owned();
+
+    let existing = std::fs::read_to_string(".gitignore").unwrap_or_default();
+    let ignored: Vec<_> = existing.lines().map(normalize).collect();
+    let missing: Vec<_> = entries
+        .iter()
+        .filter(|entry| ignored.contains(&normalize(entry)) == false)
+        .collect();
+    if missing.is_empty() {
+        return Ok(false);
+    }
+
+    let mut output = existing;
+    if output.is_empty() == false && output.ends_with('\n') == false {
+        output.push('\n');
+    }
+    output.push_str("\n# adam\n");
+    for entry in missing {
+        output.push_str(entry);
+        output.push('\n');
+    }
+    std::fs::write(".gitignore", output.trim_start())?;
+
+    Ok(true)
+}
+
+fn write_check_script(path: &str, template: &str, executable: bool) -> crate::AnyResult<()> {
+    let path = Utf8Path::new(path);
+    if path.exists() {
+        println!(
+            "{} `{}`, which already exists",
+            console::style("kept").dim(),
+            path
+        );
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, template)?;
+
+    #[cfg(unix)]
+    if executable {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = executable;
+
+    println!("{} `{}`", console::style("wrote").green().bright(), path);
+
+    Ok(())
+}
+
+const CHECK_SCRIPT_NIX_TEMPLATE: &str = "#!/bin/sh
+# adam runs this before every build on macOS and Linux, and stops the build if it fails.
+# These variables describe the build: ADAM_CHECK, ADAM_YYC, ADAM_CONFIG, ADAM_TARGET,
+# ADAM_VERBOSITY, ADAM_OUTPUT_FOLDER, and ADAM_IGNORE_CACHE.
+set -e
+
+exit 0
+";
+
+const CHECK_SCRIPT_WINDOWS_TEMPLATE: &str =
+    "# adam runs this before every build on Windows, and stops the build if it fails.
+# These variables describe the build: $env:ADAM_CHECK, $env:ADAM_YYC, $env:ADAM_CONFIG,
+# $env:ADAM_TARGET, $env:ADAM_VERBOSITY, $env:ADAM_OUTPUT_FOLDER, and $env:ADAM_IGNORE_CACHE.
+$ErrorActionPreference = \"Stop\"
+
+exit 0
+";
+
+struct Prompt {
+    interactive: bool,
+}
+
+impl Prompt {
+    /// Asks for a value, returning the default if the answer is blank or we can't ask.
+    fn ask(&self, question: &str, default: &str) -> std::io::Result<String> {
+        if self.interactive == false {
+            return Ok(default.to_owned());
+        }
+
+        if default.is_empty() {
+            print!("{}: ", question);
+        } else {
+            print!("{} [{}]: ", question, default);
+        }
+        std::io::stdout().flush()?;
+
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        let answer = answer.trim();
+
+        Ok(if answer.is_empty() {
+            default.to_owned()
+        } else {
+            answer.to_owned()
+        })
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> std::io::Result<bool> {
+        let answer = self.ask(question, if default { "y" } else { "n" })?;
+
+        Ok(matches!(answer.to_lowercase().as_str(), "y" | "yes"))
+    }
+}
@@ -1,7 +1,13 @@
 mod cli;
+mod env_vars;
 mod get_input;
+mod keys;
 mod manifest;
 
 pub use cli::*;
-pub use get_input::{parse_inputs, Operation, RunKind};
-pub use manifest::Manifest;
+pub use env_vars::{EnvOptions, ENV_PREFIX};
+pub use get_input::{
+    local_settings_visual_studio_path, parse_inputs, read_user_info, Operation, RunKind,
+};
+pub use keys::{describe_keys, manifest_keys, ManifestKey, ValueKind};
+pub use manifest::{Manifest, LOCAL_MANIFEST_NAME, MANIFEST_NAMES};
@@ -1,7 +1,7 @@
 use camino::Utf8PathBuf;
 use clap::Parser;
 
-use crate::{DEFAULT_PLATFORM_DATA, RunOptions};
+use crate::{igor::PlatformKind, runner::MessageFormat, RunOptions, DEFAULT_PLATFORM_DATA};
 
 /// A CLI intended for use by humans and machines to build GameMakerStudio 2 projects.
 #[derive(Parser, Debug)]
@@ -13,6 +13,10 @@ pub struct InputOpts {
     /// The path to a non-standard named manifest file. Possible names are .adam, .adam.json, and adam.toml
     #[clap(short, long)]
     pub manifest: Option<std::path::PathBuf>,
+
+    /// Ignores the manifest if it cannot be read, instead of stopping with an error.
+    #[clap(long, global = true)]
+    pub allow_invalid_manifest: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -37,6 +41,8 @@ pub enum ClapOperation {
     ///
     /// `ADAM_CONFIG`:  String
     ///
+    /// `ADAM_TARGET`:  String
+    ///
     /// `ADAM_VERBOSITY`:  Number
     ///
     /// `ADAM_OUTPUT_FOLDER`:  String
@@ -108,6 +114,72 @@ pub enum ClapOperation {
     /// Edits the user's personal configuration file
     #[clap(subcommand)]
     UserConfig(UserConfigOptions),
+
+    /// Finds the runtimes installed on this machine.
+    #[clap(subcommand)]
+    Runtime(RuntimeOptions),
+
+    /// Inspects the configuration adam builds with.
+    #[clap(subcommand)]
+    Config(ConfigOptions),
+
+    /// Checks that GameMaker, the runtime, and your license are set up correctly, and explains how
+    /// to fix anything which isn't.
+    Doctor(BuildOptions),
+
+    /// Sets up adam for the project in the current directory, writing a `.adam.toml` and adding
+    /// adam's files to the `.gitignore`.
+    Init(InitOptions),
+
+    /// Shows how long each stage of the last builds took, and which stages have gotten slower.
+    Stats(StatsOptions),
+}
+
+impl ClapOperation {
+    /// Returns the build options passed to this subcommand, if it takes any.
+    pub fn build_options(&self) -> Option<&BuildOptions> {
+        match self {
+            #[cfg(target_os = "windows")]
+            ClapOperation::Build(b) => Some(b),
+            ClapOperation::Run(b)
+            | ClapOperation::Release(b)
+            | ClapOperation::Clean(b)
+            | ClapOperation::Doctor(b)
+            | ClapOperation::Stats(StatsOptions {
+                build_options: b, ..
+            })
+            | ClapOperation::Check {
+                build_options: b, ..
+            }
+            | ClapOperation::Test {
+                build_options: b, ..
+            } => Some(b),
+            ClapOperation::Runtime(request) => match request {
+                RuntimeOptions::List(b)
+                | RuntimeOptions::Which(b)
+                | RuntimeOptions::Install {
+                    build_options: b, ..
+                } => Some(b),
+            },
+            ClapOperation::Config(request) => match request {
+                ConfigOptions::Validate(b)
+                | ConfigOptions::Show {
+                    build_options: b, ..
+                } => Some(b),
+                ConfigOptions::Schema => None,
+            },
+            ClapOperation::Reserialize
+            | ClapOperation::Edit(_)
+            | ClapOperation::Script(_)
+            | ClapOperation::Object(_)
+            | ClapOperation::Shader(_)
+            | ClapOperation::Remove { .. }
+            | ClapOperation::Rename { .. }
+            | ClapOperation::Folder(_)
+            | ClapOperation::UserConfig(_)
+            | ClapOperation::Init(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Parser)]
@@ -120,6 +192,47 @@ pub struct ScriptEditRequest {
     pub folder: Option<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct StatsOptions {
+    /// How many of the latest builds to show for each configuration.
+    #[clap(long, default_value_t = 10)]
+    pub last: usize,
+
+    #[clap(flatten)]
+    pub build_options: BuildOptions,
+}
+
+#[derive(Debug, Parser)]
+pub struct InitOptions {
+    /// The yyp to build. If there are several yyps, and this isn't given, we ask which one.
+    #[clap(long)]
+    pub yyp: Option<String>,
+
+    /// The Gms2 configuration to build.
+    #[clap(short, long)]
+    pub config: Option<String>,
+
+    /// The folder to build into, relative to the project.
+    #[clap(short, long)]
+    pub output_folder: Option<Utf8PathBuf>,
+
+    /// The keyword the game prints when `adam test` passes.
+    #[clap(long)]
+    pub test_success_keyword: Option<String>,
+
+    /// Writes check scripts, which run before every build, and sets them in the manifest.
+    #[clap(long)]
+    pub check_scripts: bool,
+
+    /// Uses the defaults for anything not given, instead of asking.
+    #[clap(short, long)]
+    pub yes: bool,
+
+    /// Replaces the project's manifest if it already has one.
+    #[clap(long)]
+    pub force: bool,
+}
+
 #[derive(Debug, Parser)]
 pub struct ShaderEditRequest {
     /// The name of the shader, such as `shd_outline`. Do not include any file extension.
@@ -201,15 +314,97 @@ pub enum UserConfigOptions {
     /// Prints out the full path to the user configuration file. If one does not exist, it is created.
     Path,
 
-    /// Saves a given key value pair in the user config.
-    Edit {
-        /// The name of the property.
-        name: String,
-        /// The value of the property.
+    /// Prints the value of a key in the user config.
+    Get {
+        /// The key, such as `runtime`.
+        key: String,
+    },
+
+    /// Sets a key in the user config. Lists, like `test_env_variables`, are separated by commas.
+    #[clap(alias = "edit")]
+    Set {
+        /// The key, such as `runtime`.
+        key: String,
+        /// The value, such as `2024.6.0.205`.
+        value: String,
+    },
+
+    /// Removes a key from the user config, so that it goes back to its default.
+    Unset {
+        /// The key, such as `runtime`.
+        key: String,
+    },
+
+    /// Adds a value to a list in the user config, such as `test_env_variables`.
+    Add {
+        /// The key of the list.
+        key: String,
+        /// The value to add.
+        value: String,
+    },
+
+    /// Removes a value from a list in the user config, such as `test_env_variables`.
+    Remove {
+        /// The key of the list.
+        key: String,
+        /// The value to remove.
         value: String,
     },
 }
 
+#[derive(Parser, Debug, PartialEq, Eq, Clone)]
+pub enum ConfigOptions {
+    /// Checks the manifest and user config for mistakes, such as paths which do not exist,
+    /// configurations which are not in the yyp, and missing check scripts.
+    Validate(BuildOptions),
+
+    /// Prints the final options which a build would use, and whether each one came from the
+    /// defaults, the user config, the project manifest, an environment variable, or a CLI flag.
+    Show {
+        /// Prints the options as json, for scripts.
+        #[clap(long)]
+        json: bool,
+
+        #[clap(flatten)]
+        build_options: BuildOptions,
+    },
+
+    /// Prints a JSON Schema for the manifest, for editors to complete and check `adam.toml`.
+    Schema,
+}
+
+#[derive(Parser, Debug, PartialEq, Eq, Clone)]
+pub enum RuntimeOptions {
+    /// Lists every installed runtime, marking the one which would be used to build.
+    List(BuildOptions),
+
+    /// Prints the path and version of the runtime which would be used to build, and why it was chosen.
+    Which(BuildOptions),
+
+    /// Installs a runtime into the runtimes folder, from the runtime feed or from a local archive.
+    Install {
+        /// The version to install, such as `2024.6.0.205`.
+        version: String,
+
+        /// Installs from this zip archive instead of downloading the runtime. The archive is still
+        /// checked against the feed, if the feed lists the version.
+        #[clap(long)]
+        from: Option<Utf8PathBuf>,
+
+        /// The url or path of the runtime feed to install from. Defaults to `runtime_feed` in the
+        /// config, or YoYo's runtime feed.
+        #[clap(long)]
+        feed: Option<String>,
+
+        /// Reinstalls the runtime, even if it is already installed.
+        #[clap(long)]
+        force: bool,
+
+        #[clap(flatten)]
+        build_options: BuildOptions,
+    },
+}
+
 /// The kinds of things which can be added to a project.
 #[derive(Parser, Debug, PartialEq, Eq, Clone, Ord, PartialOrd)]
 pub enum FolderRequest {
@@ -259,6 +454,26 @@ pub struct BuildOptions {
     #[clap(long)]
     no_build_script: bool,
 
+    /// Writes the build files, such as `build.bff` and `macros.json`, and prints the Igor or
+    /// GMAssetCompiler command line and environment, but doesn't run anything. With `check` and
+    /// `clean`, prints the check script it would run or the folder it would remove instead.
+    #[clap(long)]
+    pub dry_run: bool,
+
+    /// Prints the Igor or GMAssetCompiler command line and environment before running it.
+    #[clap(long)]
+    pub print_command: bool,
+
+    /// How to report the build. `json` writes one JSON object per line, described in
+    /// `docs/MESSAGE_FORMAT.md`, for editors and CI. `short` writes each compile error on one
+    /// line, for editors' problem matchers.
+    #[clap(long, value_enum)]
+    pub message_format: Option<MessageFormat>,
+
+    /// Writes the compile's errors to this file as SARIF 2.1, for code review annotations.
+    #[clap(long)]
+    pub diagnostics_out: Option<Utf8PathBuf>,
+
     /// Uses the YYC instead of the default VM. If this is the case, then we'll need to check
     /// your Visual Studio path on Windows.
     #[clap(long, short)]
@@ -290,10 +505,27 @@ pub struct BuildOptions {
     #[clap(short, long)]
     pub config: Option<String>,
 
+    /// Applies a `[profile.<name>]` from the manifest, over the rest of the manifest. Flags passed
+    /// on the command line still override the profile.
+    #[clap(long)]
+    pub profile: Option<String>,
+
     /// Specifies the target Yyp to build, if there are multiple.
     #[clap(long)]
     pub yyp: Option<String>,
 
+    /// Runs this command in every member listed in the manifest's `[workspace]` table,
+    /// and reports on all of them at the end.
+    #[clap(long)]
+    pub workspace: bool,
+
+    /// The platform to build for, such as `linux` or `windows`. If not passed, we build for
+    /// the platform adam is running on.
+    ///
+    /// Output is placed in `<output_folder>/<target>/<vm|yyc>`.
+    #[clap(long, value_enum)]
+    pub target: Option<PlatformKind>,
+
     /// Verbosity level. Can use multiple times, like '-vv'. >0 disables pretty compiles, >1 enables igor verbosity, >2 enables gmac verbosity
     #[clap(short, long)]
     #[arg(action(clap::ArgAction::Count))]
@@ -312,7 +544,8 @@ pub struct BuildOptions {
     #[clap(long)]
     pub gms2_install_location: Option<Utf8PathBuf>,
 
-    /// If the non-current runtime is desired, it can be set here. We default right now to `2.3.1.409` on stable and beta.
+    /// If a specific runtime is desired, it can be set here, such as `2024.6.0.205`. If not set, we use the
+    /// newest installed runtime from the same release as the project, or else the newest installed runtime.
     #[clap(short, long)]
     pub runtime: Option<String>,
 
@@ -347,6 +580,26 @@ pub struct BuildOptions {
     /// If true, will try to find the PID of a runner game and force it to close.
     #[clap(long)]
     pub close_on_sig_kill: bool,
+
+    /// The number of jobs Igor and GMAssetCompiler compile with. Defaults to 8.
+    #[clap(short, long)]
+    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: Option<u32>,
+
+    /// An extra argument to pass to Igor, such as `--igor-arg=-showtimings`. Can be used multiple
+    /// times, and is added to any `igor_args` in the manifest, replacing the same flag there.
+    #[clap(long, allow_hyphen_values = true)]
+    pub igor_arg: Vec<String>,
+
+    /// An extra argument to pass to GMAssetCompiler, when adam runs it directly for `adam build`
+    /// on Windows. Can be used multiple times, and is added to any `asset_compiler_args` in the
+    /// manifest, replacing the same flag there.
+    #[clap(long, allow_hyphen_values = true)]
+    pub asset_compiler_arg: Vec<String>,
+
+    /// Fails the build if the GML compile gives any warnings.
+    #[clap(long)]
+    pub deny_warnings: bool,
 }
 
 impl BuildOptions {
@@ -359,16 +612,48 @@ impl BuildOptions {
         if self.no_build_script {
             run_options.task.no_build_script = true;
         }
+        if self.dry_run {
+            run_options.task.dry_run = true;
+        }
+        if self.print_command {
+            run_options.task.print_command = true;
+        }
+        if let Some(message_format) = self.message_format {
+            run_options.task.message_format = message_format;
+        }
+        if let Some(diagnostics_out) = self.diagnostics_out {
+            run_options.task.diagnostics_out = Some(diagnostics_out);
+        }
 
         if let Some(cfg) = self.config {
             run_options.task.config = cfg;
         }
+        if let Some(target) = self.target {
+            run_options.task.target = target;
+        }
+        if let Some(yyp) = self.yyp {
+            run_options.task.yyp = Some(yyp);
+        }
+        if self.workspace {
+            run_options.task.workspace = true;
+        }
         if let Some(of) = self.output_folder {
             run_options.task.output_folder = of;
         }
         if let Some(gms2) = self.gms2_install_location {
             run_options.platform.gms2_application_location = gms2;
         }
+        // the beta goes first, so that a `runtime` can still be picked within it
+        if self.beta {
+            run_options.platform.gms2_application_location =
+                DEFAULT_PLATFORM_DATA.beta_application_path.into();
+
+            run_options.platform.runtime_location =
+                DEFAULT_PLATFORM_DATA.beta_runtime_location.clone();
+            run_options.platform.runtime_pinned = false;
+
+            run_options.platform.compiler_cache = DEFAULT_PLATFORM_DATA.beta_cached_data.clone();
+        }
         if let Some(runtime) = self.runtime {
             let path = run_options
                 .platform
@@ -377,9 +662,11 @@ impl BuildOptions {
                 .unwrap()
                 .join(format!("runtime-{}", runtime));
             run_options.platform.runtime_location = path;
+            run_options.platform.runtime_pinned = true;
         }
         if let Some(runtime_location_override) = self.runtime_location_override {
             run_options.platform.runtime_location = runtime_location_override;
+            run_options.platform.runtime_pinned = true;
         }
         if let Some(visual_studio_path) = self.visual_studio_path {
             run_options.platform.visual_studio_path = visual_studio_path;
@@ -388,9 +675,9 @@ impl BuildOptions {
             run_options.platform.user_license_folder = user_license_folder;
         }
 
-        // Macos never has a visual studio path!
+        // Macos and Linux never have a visual studio path!
         // good stuff, eh?
-        #[cfg(target_os = "macos")]
+        #[cfg(not(target_os = "windows"))]
         {
             run_options.platform.visual_studio_path = Default::default();
         }
@@ -399,15 +686,6 @@ impl BuildOptions {
             run_options.task.no_user_folder = true;
         }
 
-        if self.beta {
-            run_options.platform.gms2_application_location =
-                DEFAULT_PLATFORM_DATA.beta_application_path.into();
-
-            run_options.platform.runtime_location =
-                DEFAULT_PLATFORM_DATA.beta_runtime_location.into();
-
-            run_options.platform.compiler_cache = DEFAULT_PLATFORM_DATA.beta_cached_data.clone();
-        }
         if self.verbosity != 0 {
             run_options.task.verbosity = self.verbosity;
         }
@@ -415,8 +693,10 @@ impl BuildOptions {
         // if we say to use the yyc, we use the yyc
         if self.yyc {
             run_options.task.yyc = true;
-        } else {
-            // we just set the visual studio path here..
+        }
+        // the yyc might have been turned on by the manifest instead, so we only drop the visual
+        // studio path once we know it's off
+        if run_options.task.yyc == false {
             run_options.platform.visual_studio_path = Default::default();
         }
 
@@ -427,5 +707,50 @@ impl BuildOptions {
         if self.close_on_sig_kill {
             run_options.task.close_on_sig_kill = self.close_on_sig_kill;
         }
+
+        if let Some(jobs) = self.jobs {
+            run_options.task.jobs = jobs;
+        }
+        crate::runner::merge_args(&mut run_options.task.igor_args, self.igor_arg);
+        crate::runner::merge_args(
+            &mut run_options.task.asset_compiler_args,
+            self.asset_compiler_arg,
+        );
+        if self.deny_warnings {
+            run_options.task.deny_warnings = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_manifest_can_turn_on_the_yyc_and_set_its_visual_studio_path() {
+        let manifest: crate::input::Manifest = toml::from_str(
+            r#"
+            yyc = true
+            visual_studio_path = "vs/vcvars32.bat"
+            "#,
+        )
+        .unwrap();
+
+        let mut options = crate::default_run_options();
+        manifest.write_to_options(&mut options, &mut None);
+        BuildOptions::default().write_to_options(&mut options);
+
+        assert!(options.task.yyc);
+        // only windows uses visual studio, so everyone else always drops it
+        if cfg!(target_os = "windows") {
+            assert_eq!(options.platform.visual_studio_path, "vs/vcvars32.bat");
+        } else {
+            assert_eq!(options.platform.visual_studio_path, "");
+        }
+
+        let mut options = crate::default_run_options();
+        options.platform.visual_studio_path = "vs/vcvars32.bat".into();
+        BuildOptions::default().write_to_options(&mut options);
+        assert_eq!(options.platform.visual_studio_path, "");
     }
 }
@@ -0,0 +1,212 @@
+use clap::{Args, FromArgMatches};
+use color_eyre::eyre::{bail, eyre, WrapErr};
+
+use super::{manifest_keys, BuildOptions, Manifest, ValueKind};
+use crate::AnyResult;
+
+/// The prefix of the environment variables which set options, such as `ADAM_RUNTIME`.
+pub const ENV_PREFIX: &str = "ADAM_";
+
+/// Variables which adam sets for check scripts and the game, and which aren't options.
+const ADAM_VARS: &[&str] = &[
+    "ADAM_CHECK",
+    "ADAM_PROJECT_PATH",
+    "ADAM_TEST",
+    "ADAM_IPC_SOCKET",
+];
+
+/// Variables which adam sets for check scripts, and which share their name with an option. In a
+/// check script, where `ADAM_CHECK` is set, these describe the build running the script, so an
+/// adam started by the script doesn't read them as its own options.
+const CHECK_SCRIPT_VARS: &[&str] = &[
+    "ADAM_YYC",
+    "ADAM_CONFIG",
+    "ADAM_TARGET",
+    "ADAM_VERBOSITY",
+    "ADAM_OUTPUT_FOLDER",
+    "ADAM_IGNORE_CACHE",
+];
+
+/// Options set with `ADAM_*` environment variables. Each variable is named after a manifest key
+/// or a CLI flag, so `ADAM_OUTPUT_FOLDER` sets `output_folder` and `ADAM_PROFILE` sets `--profile`.
+///
+/// These apply over every config file, and under the CLI.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EnvOptions {
+    /// The variables named after manifest keys.
+    pub manifest: Manifest,
+
+    /// The variables named after flags which aren't manifest keys, such as `ADAM_PROFILE`.
+    pub build_options: BuildOptions,
+
+    /// `ADAM_*` variables which are not options at all.
+    pub unknown: Vec<String>,
+}
+
+impl EnvOptions {
+    /// Reads the options out of this process's environment.
+    pub fn from_env() -> AnyResult<Self> {
+        Self::from_vars(std::env::vars())
+    }
+
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> AnyResult<Self> {
+        let keys = manifest_keys();
+        let mut command = BuildOptions::augment_args(clap::Command::new("adam"));
+
+        let mut output = EnvOptions::default();
+        let mut args = vec!["adam".to_owned()];
+
+        let vars: Vec<_> = vars.into_iter().collect();
+        let in_check_script = vars
+            .iter()
+            .any(|(name, value)| name == "ADAM_CHECK" && value.is_empty() == false);
+
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            // an empty variable counts as unset, since that's easier to do in CI than unsetting it
+            if value.is_empty() || ADAM_VARS.contains(&name.as_str()) {
+                continue;
+            }
+            if in_check_script && CHECK_SCRIPT_VARS.contains(&name.as_str()) {
+                continue;
+            }
+            let key = key.to_lowercase();
+
+            // tables can't be set from a variable, so `ADAM_PROFILE` and `ADAM_WORKSPACE` are
+            // read as the CLI flags of the same name instead
+            let manifest_key = keys
+                .iter()
+                .find(|v| v.name == key && v.kind != ValueKind::Table);
+            if let Some(manifest_key) = manifest_key {
+                if manifest_key.deprecated {
+                    bail!("`{}` is deprecated", name);
+                }
+
+                let value = manifest_key.kind.parse(&value).ok_or_else(|| {
+                    eyre!(
+                        "`{}` is `{}`, which is not a {}",
+                        name,
+                        value,
+                        manifest_key.kind
+                    )
+                })?;
+
+                let edit: Manifest = serde_json::from_value(serde_json::json!({ key: value }))
+                    .wrap_err_with(|| format!("invalid `{}`", name))?;
+                edit.apply_on(&mut output.manifest);
+
+                continue;
+            }
+
+            let Some(arg) = command
+                .get_arguments()
+                .find(|v| v.get_id().as_str() == key && v.get_long().is_some())
+            else {
+                output.unknown.push(name);
+                continue;
+            };
+
+            let flag = format!("--{}", arg.get_long().unwrap());
+            if arg.get_action().takes_values() {
+                args.extend([flag, value]);
+                continue;
+            }
+
+            match ValueKind::Boolean.parse(&value) {
+                Some(serde_json::Value::Bool(true)) => args.push(flag),
+                Some(_) => {}
+                None => bail!("`{}` is `{}`, which is not a boolean", name, value),
+            }
+        }
+
+        let matches = command
+            .try_get_matches_from_mut(args)
+            .map_err(|e| eyre!("invalid `{}*` variable: {}", ENV_PREFIX, e.kind()))?;
+        output.build_options = BuildOptions::from_arg_matches(&matches)?;
+
+        // `ADAM_CONFIG` is the same as `ADAM_CONFIGURATION`, just like `-c` is
+        if let Some(config) = output.build_options.config.take() {
+            output.manifest.configuration.name = Some(config);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> AnyResult<EnvOptions> {
+        EnvOptions::from_vars(
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+    }
+
+    #[test]
+    fn reads_manifest_keys_and_flags() {
+        let env = vars(&[
+            ("ADAM_CONFIGURATION", "Release"),
+            ("ADAM_YYC", "1"),
+            ("ADAM_VERBOSITY", "2"),
+            ("ADAM_TARGET", "ubuntu"),
+            ("ADAM_OUTPUT_FOLDER", "out"),
+            ("ADAM_TEST_ENV_VARIABLES", "CI, HEADLESS"),
+            ("ADAM_PROFILE", "ship"),
+            ("ADAM_WORKSPACE", "true"),
+            ("ADAM_RUNTIME", ""),
+            ("ADAM_SMITH", "economist"),
+            ("ADAM_PROJECT_PATH", "/home/me/game"),
+            ("ADAM_IPC_SOCKET", "/tmp/ipc_log.log"),
+            ("PATH", "/usr/bin"),
+        ])
+        .unwrap();
+
+        assert_eq!(env.manifest.configuration.name.as_deref(), Some("Release"));
+        assert_eq!(env.manifest.yyc, Some(true));
+        assert_eq!(env.manifest.verbosity, Some(2));
+        assert_eq!(env.manifest.target, Some(crate::igor::PlatformKind::Linux));
+        assert_eq!(env.manifest.output_folder.as_deref(), Some("out".into()));
+        assert_eq!(env.manifest.test_env_variables, ["CI", "HEADLESS"]);
+        assert_eq!(env.manifest.runtime, None);
+        assert_eq!(env.build_options.profile.as_deref(), Some("ship"));
+        assert!(env.build_options.workspace);
+        assert_eq!(env.unknown, ["ADAM_SMITH"]);
+    }
+
+    #[test]
+    fn check_scripts_dont_inherit_the_build() {
+        let env = vars(&[
+            ("ADAM_CHECK", "1"),
+            ("ADAM_YYC", "1"),
+            ("ADAM_CONFIG", "Release"),
+            ("ADAM_IGNORE_CACHE", "2"),
+            ("ADAM_RUNTIME", "2024.6.0.205"),
+        ])
+        .unwrap();
+
+        assert_eq!(env.manifest.runtime.as_deref(), Some("2024.6.0.205"));
+        assert_eq!(env.manifest.configuration.name, None);
+        assert_eq!(env.manifest.yyc, None);
+        assert!(env.unknown.is_empty());
+    }
+
+    #[test]
+    fn bad_values_name_the_variable() {
+        let err = vars(&[("ADAM_YYC", "sure")]).unwrap_err().to_string();
+        assert_eq!(err, "`ADAM_YYC` is `sure`, which is not a boolean");
+
+        let err = format!("{:#}", vars(&[("ADAM_TARGET", "amiga")]).unwrap_err());
+        assert!(
+            err.starts_with("invalid `ADAM_TARGET`: unknown variant"),
+            "{}",
+            err
+        );
+
+        let err = vars(&[("ADAM_X64_WINDOWS", "1")]).unwrap_err().to_string();
+        assert_eq!(err, "`ADAM_X64_WINDOWS` is deprecated");
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use camino::{Utf8Path, Utf8PathBuf};
-use color_eyre::Help;
+use color_eyre::eyre::WrapErr;
 
 use crate::{AnyResult, RunOptions};
 
@@ -90,6 +90,11 @@ pub fn parse_inputs(
 
         // we won't get here for these
         ClapOperation::UserConfig(_)
+        | ClapOperation::Runtime(_)
+        | ClapOperation::Doctor(_)
+        | ClapOperation::Stats(_)
+        | ClapOperation::Config(_)
+        | ClapOperation::Init(_)
         | ClapOperation::Edit(_)
         | ClapOperation::Folder { .. }
         | ClapOperation::Script(_)
@@ -128,7 +133,7 @@ pub fn load_user_data(options: &mut RunOptions) -> AnyResult {
             "`no-user-folder` is set, but `user-license-folder` is not set."
         };
 
-        println!(
+        adam_print!(
             "{}: {}",
             console::style("adam error").bright().red(),
             console::style(msg).bold()
@@ -137,62 +142,24 @@ pub fn load_user_data(options: &mut RunOptions) -> AnyResult {
         std::process::exit(1);
     }
 
-    let um_json_path = options.platform.compiler_cache.join("um.json");
-    let um_json: serde_json::Value = serde_json::from_str(
-        &std::fs::read_to_string(&um_json_path)
-            .with_note(|| format!("Could not read path {}", um_json_path))?,
-    )
-    .with_note(|| "Couldn't parse `um.json` file.")?;
-
-    let user_id: usize = match um_json.get("userID").unwrap().as_str().unwrap().parse() {
-        Ok(v) => v,
-        Err(_) => {
-            println!(
-                "{}: invalid `userID` found. are you logged in?",
-                console::style("adam error").bright().red(),
-            );
-
-            std::process::exit(1);
-        }
-    };
-
-    let user_name = um_json
-        .get("login")
-        .unwrap()
-        .as_str()
-        .unwrap()
-        .split('@')
-        .next()
-        .unwrap()
-        .to_owned();
+    let user = read_user_info(&options.platform.compiler_cache)?;
 
     if options.platform.user_license_folder.exists() == false {
         options.platform.user_license_folder = options
             .platform
             .compiler_cache
-            .join(format!("{}_{}", user_name, user_id));
+            .join(user.license_folder_name());
     }
 
     // we need a visual studio path...
     if cfg!(target_os = "windows") && options.platform.visual_studio_path.exists() == false {
         // the ide can give us one...
-        let new_path = std::fs::read_to_string(
-            options
+        let new_path = local_settings_visual_studio_path(
+            &options
                 .platform
                 .compiler_cache
-                .join(format!("{}_{}/local_settings.json", user_name, user_id)),
+                .join(user.license_folder_name()),
         )
-        .ok()
-        .and_then(|data| {
-            let local_settings: serde_json::Value = serde_json::from_str(&data).unwrap();
-
-            local_settings
-                .get("machine.Platform Settings.Windows.visual_studio_path")
-                .map(|v| {
-                    let v = v.as_str().unwrap();
-                    Utf8Path::new(v).to_owned()
-                })
-        })
         .unwrap_or_else(|| {
             Utf8Path::new("C:/Program Files (x86)/Microsoft Visual Studio 14.0/VC/bin/vcvars32.bat")
                 .to_owned()
@@ -203,3 +170,58 @@ pub fn load_user_data(options: &mut RunOptions) -> AnyResult {
 
     Ok(())
 }
+
+/// The user who is logged into the IDE, as recorded in `um.json`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UserInfo {
+    pub user_name: String,
+    pub user_id: usize,
+}
+
+impl UserInfo {
+    /// The name of the folder within the compiler cache which holds this user's license.
+    pub fn license_folder_name(&self) -> String {
+        format!("{}_{}", self.user_name, self.user_id)
+    }
+}
+
+/// Reads the logged in user out of the `um.json` in the compiler cache.
+pub fn read_user_info(compiler_cache: &Utf8Path) -> AnyResult<UserInfo> {
+    let um_json_path = compiler_cache.join("um.json");
+    let um_json: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(&um_json_path)
+            .wrap_err_with(|| format!("could not read `{}`", um_json_path))?,
+    )
+    .wrap_err_with(|| format!("could not parse `{}`", um_json_path))?;
+
+    let Some(user_id) = um_json
+        .get("userID")
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.parse().ok())
+    else {
+        color_eyre::eyre::bail!(
+            "invalid `userID` found in `{}`. are you logged in?",
+            um_json_path
+        );
+    };
+
+    let Some(login) = um_json.get("login").and_then(|v| v.as_str()) else {
+        color_eyre::eyre::bail!("no `login` found in `{}`. are you logged in?", um_json_path);
+    };
+
+    Ok(UserInfo {
+        user_name: login.split('@').next().unwrap_or_default().to_owned(),
+        user_id,
+    })
+}
+
+/// Reads the visual studio path which the IDE saved in the license folder's `local_settings.json`.
+pub fn local_settings_visual_studio_path(license_folder: &Utf8Path) -> Option<Utf8PathBuf> {
+    let data = std::fs::read_to_string(license_folder.join("local_settings.json")).ok()?;
+    let local_settings: serde_json::Value = serde_json::from_str(&data).ok()?;
+
+    local_settings
+        .get("machine.Platform Settings.Windows.visual_studio_path")
+        .and_then(|v| v.as_str())
+        .map(Utf8PathBuf::from)
+}
@@ -0,0 +1,149 @@
+use super::Manifest;
+
+/// Manifest keys which only mean something inside a project's manifest file.
+const FILE_ONLY_KEYS: &[&str] = &["extends", "inherits"];
+
+/// A top-level key of the manifest, such as `runtime`, and the type of its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestKey {
+    pub name: String,
+    pub kind: ValueKind,
+    pub deprecated: bool,
+}
+
+/// The type of a manifest key's value, as it's written on the command line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueKind {
+    Boolean,
+    Number,
+    String,
+    /// A list of strings, written separated by commas.
+    List,
+    /// A table, such as `[profile.ship]`, which can only be written in a file.
+    Table,
+}
+
+impl ValueKind {
+    /// Reads a value written on the command line. Returns `None` if it's not of this kind.
+    pub fn parse(self, value: &str) -> Option<serde_json::Value> {
+        match self {
+            ValueKind::Boolean => match value.to_lowercase().as_str() {
+                "true" | "1" => Some(serde_json::Value::Bool(true)),
+                "false" | "0" => Some(serde_json::Value::Bool(false)),
+                _ => None,
+            },
+            ValueKind::Number => value
+                .parse::<u64>()
+                .ok()
+                .map(|v| serde_json::Value::Number(v.into())),
+            ValueKind::String => Some(serde_json::Value::String(value.to_owned())),
+            ValueKind::List => Some(
+                value
+                    .split(',')
+                    .map(|v| v.trim())
+                    .filter(|v| v.is_empty() == false)
+                    .collect(),
+            ),
+            ValueKind::Table => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let w = match self {
+            ValueKind::Boolean => "boolean",
+            ValueKind::Number => "number",
+            ValueKind::String => "string",
+            ValueKind::List => "list",
+            ValueKind::Table => "table",
+        };
+
+        f.pad(w)
+    }
+}
+
+/// Every top-level key of the manifest which can be set outside of a project's manifest file, in
+/// the order they're declared. The kinds are read from the manifest's schema, so they always
+/// match the fields of [`Manifest`].
+pub fn manifest_keys() -> Vec<ManifestKey> {
+    let schema = serde_json::to_value(schemars::schema_for!(Manifest)).unwrap();
+    let definitions = &schema["definitions"];
+
+    schema["properties"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| FILE_ONLY_KEYS.contains(&name.as_str()) == false)
+        .map(|(name, property)| ManifestKey {
+            name: name.clone(),
+            kind: schema_kind(property, definitions).unwrap_or(ValueKind::String),
+            deprecated: property["deprecated"] == true,
+        })
+        .collect()
+}
+
+/// Lists the keys with their kinds, for error messages, such as `yyc (boolean), runtime (string)`.
+pub fn describe_keys(keys: &[ManifestKey]) -> String {
+    keys.iter()
+        .filter(|v| v.deprecated == false)
+        .map(|v| format!("{} ({})", v.name, v.kind))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Finds the kind of a schema, following references. `null` is skipped, since every key can be
+/// left out, and for a key which takes one of several kinds, the first is used.
+fn schema_kind(schema: &serde_json::Value, definitions: &serde_json::Value) -> Option<ValueKind> {
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.rsplit('/').next()?;
+        return schema_kind(&definitions[name], definitions);
+    }
+
+    let types: Vec<&str> = match &schema["type"] {
+        serde_json::Value::String(kind) => vec![kind],
+        serde_json::Value::Array(kinds) => kinds.iter().filter_map(|v| v.as_str()).collect(),
+        _ => vec![],
+    };
+    let kind = types.into_iter().find_map(|kind| match kind {
+        "boolean" => Some(ValueKind::Boolean),
+        "integer" => Some(ValueKind::Number),
+        "string" => Some(ValueKind::String),
+        "array" => Some(ValueKind::List),
+        "object" => Some(ValueKind::Table),
+        _ => None,
+    });
+    if kind.is_some() {
+        return kind;
+    }
+
+    ["allOf", "anyOf"]
+        .iter()
+        .filter_map(|key| schema[key].as_array())
+        .flatten()
+        .find_map(|v| schema_kind(v, definitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_follow_the_manifest_fields() {
+        let keys = manifest_keys();
+        let kind = |name: &str| keys.iter().find(|v| v.name == name).map(|v| v.kind);
+
+        assert_eq!(kind("yyc"), Some(ValueKind::Boolean));
+        assert_eq!(kind("verbosity"), Some(ValueKind::Number));
+        assert_eq!(kind("runtime"), Some(ValueKind::String));
+        assert_eq!(kind("target"), Some(ValueKind::String));
+        assert_eq!(kind("configuration"), Some(ValueKind::String));
+        assert_eq!(kind("output_folder"), Some(ValueKind::String));
+        assert_eq!(kind("test_env_variables"), Some(ValueKind::List));
+        assert_eq!(kind("workspace"), Some(ValueKind::Table));
+        assert_eq!(kind("profile"), Some(ValueKind::Table));
+        assert_eq!(kind("extends"), None);
+
+        assert!(keys.iter().any(|v| v.name == "x64_windows" && v.deprecated));
+    }
+}
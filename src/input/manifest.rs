@@ -1,14 +1,37 @@
 use camino::Utf8PathBuf;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
-use crate::DEFAULT_PLATFORM_DATA;
+use crate::{igor::PlatformKind, runner::MarkerSet, AnyResult, DEFAULT_PLATFORM_DATA};
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Default, Clone, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct Manifest {
     /// the Gms2 configuration to use. If blank, will use "Default".
-    pub configuration: Option<String>,
+    ///
+    /// Instead of a name, this can be a set of `[configuration.<Name>]` tables, which are applied
+    /// over the rest of the manifest when building that configuration.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Configuration::is_empty")]
+    pub configuration: Configuration,
+
+    /// uses the YYC instead of the VM. Defaults to false.
+    pub yyc: Option<bool>,
+
+    /// the platform to build for, such as `linux` or `windows`. If blank, will use the host platform.
+    pub target: Option<PlatformKind>,
+
+    /// the name of the yyp to build. Required if there are multiple yyps in the directory.
+    pub yyp: Option<String>,
+
+    /// the project directories to build with `--workspace`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<Workspace>,
 
     /// the verbosity to use in the compiler.
     /// >0 disable the pretty compile widget
@@ -17,6 +40,7 @@ pub struct Manifest {
     pub verbosity: Option<u8>,
 
     /// The output folder, relative to the current working directory. Defaults to `target`
+    #[schemars(with = "Option<String>")]
     pub output_folder: Option<Utf8PathBuf>,
 
     /// Ignore cache.
@@ -29,6 +53,7 @@ pub struct Manifest {
     /// On Windows, this defaults to `C:\Program Files\GameMaker Studio 2\GameMaker.exe`.
     /// On macOS, this default to `/Applications/GameMaker.app`. (For macOS, you can point to just
     /// the .app -- internally, we will search inside the app bundle for the executable)
+    #[schemars(with = "Option<String>")]
     pub gms2_install_location: Option<Utf8PathBuf>,
 
     /// Option to switch to using the Gms2 Beta. By default, this will use the `C:/Program Files/GameMaker Studio 2 Beta/GameMakerStudio-Beta.exe`
@@ -37,12 +62,21 @@ pub struct Manifest {
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub beta: bool,
 
-    /// If the non-current runtime is desired, it can be set here. We default right now to `2.3.1.409` on stable and beta.
+    /// If a specific runtime is desired, it can be set here, such as `2024.6.0.205`. If not set, we use the
+    /// newest installed runtime from the same release as the project, or else the newest installed runtime.
     pub runtime: Option<String>,
 
+    /// The url or path of the rss feed which `adam runtime install` installs runtimes from. Defaults to
+    /// YoYo's runtime feed, but can point to an internal mirror.
+    #[serde(default)]
+    pub runtime_feed: Option<String>,
+
+    /// Deprecated, and ignored. Set up x64 in a Gms2 configuration instead.
     #[deprecated]
     #[serde(default)]
     #[serde(skip_serializing)]
+    // schemars marks skipped fields as required, even with a default, so we describe it as optional
+    #[schemars(with = "Option<bool>")]
     pub x64_windows: bool,
 
     /// If this option is set, then we will not read your `~/.config/GameMakerStudio2` or `%APPDATA%/GameMakerStudio2` folders
@@ -54,6 +88,7 @@ pub struct Manifest {
 
     /// This sets a complete path to the runtime location.
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub runtime_location_override: Option<Utf8PathBuf>,
 
     /// Use this visual studio path, instead of the visual studio path within the `user_folder`
@@ -70,6 +105,7 @@ pub struct Manifest {
     /// If this field and `user_license_folder` are both set, then we will not look in your
     /// `user_folder` at all. To ensure we don't do that, pass `-no-user-folder`.
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub visual_studio_path: Option<Utf8PathBuf>,
 
     /// Use this folder for the user_license, instead of the path within the `user_folder`
@@ -78,8 +114,27 @@ pub struct Manifest {
     /// If this field and `visual_studio_path` are both set, then we will not look in your
     /// `user_folder` at all.
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub user_license_folder: Option<Utf8PathBuf>,
 
+    /// The number of jobs Igor and GMAssetCompiler compile with. Defaults to 8.
+    pub jobs: Option<u32>,
+
+    /// Extra arguments to pass to Igor, such as `["-showtimings"]`. These can't repeat a flag
+    /// which adam already passes, like `-j` or `-options`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub igor_args: Vec<String>,
+
+    /// Extra arguments to pass to GMAssetCompiler, which adam runs directly for `adam build` on
+    /// Windows. These can't repeat a flag which adam already passes, like `--rt` or `--tgt`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub asset_compiler_args: Vec<String>,
+
+    /// If true, any warning in the GML compile fails the build. Defaults to false.
+    pub deny_warnings: Option<bool>,
+
     /// A list of environment variable names that will be set to "1" if running `adam test`.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -94,13 +149,169 @@ pub struct Manifest {
     ///
     /// This path is relative to the current working directory.
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub path_to_run_windows: Option<Utf8PathBuf>,
 
-    /// This is the shell script which we will run on *Nix platforms (macOS only currently).
+    /// This is the shell script which we will run on *Nix platforms (macOS and Linux).
     ///
     /// This path is relative to the current working directory.
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub path_to_run_nix: Option<Utf8PathBuf>,
+
+    /// Overrides for the lines adam looks for in Igor's output to follow a compile, such as
+    /// `compile_end = ["Final Compile...finished"]`. Use this when a new runtime rewords one of
+    /// them before adam knows about it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compiler_markers: Option<MarkerSet>,
+
+    /// Named profiles, such as `[profile.ship]`, which are selected with `--profile`. Each
+    /// profile can contain any of the keys above, which are applied over the rest of the manifest.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, Manifest>,
+
+    /// The path to another manifest, relative to this one, whose values are applied first. That
+    /// manifest can extend another, and so on.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub extends: Option<Utf8PathBuf>,
+
+    /// Within a profile, the name of another profile to apply first.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+}
+
+/// The Gms2 configuration to build, and the overrides to apply for particular configurations.
+///
+/// In a manifest, this is either a name, like `configuration = "Release"`, or a set of
+/// `[configuration.<Name>]` tables. One file can't hold both, so a manifest with overrides picks
+/// its configuration in a profile, in the user config, or with `-c`.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "ConfigurationRepr", into = "ConfigurationRepr")]
+pub struct Configuration {
+    pub name: Option<String>,
+    pub overrides: BTreeMap<String, Manifest>,
+}
+
+impl Configuration {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.overrides.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ConfigurationRepr {
+    /// the Gms2 configuration to use, such as `Release`.
+    Name(String),
+    /// `[configuration.<Name>]` tables, applied over the manifest when building that configuration.
+    Overrides(BTreeMap<String, Manifest>),
+}
+
+impl JsonSchema for Configuration {
+    fn schema_name() -> String {
+        "Configuration".to_owned()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::schema::Schema {
+        ConfigurationRepr::json_schema(generator)
+    }
+}
+
+impl From<ConfigurationRepr> for Configuration {
+    fn from(value: ConfigurationRepr) -> Self {
+        match value {
+            ConfigurationRepr::Name(name) => Configuration {
+                name: Some(name),
+                overrides: BTreeMap::new(),
+            },
+            ConfigurationRepr::Overrides(overrides) => Configuration {
+                name: None,
+                overrides,
+            },
+        }
+    }
+}
+
+impl From<Configuration> for ConfigurationRepr {
+    fn from(value: Configuration) -> Self {
+        // only a merged manifest has both, and those are never written out
+        if value.overrides.is_empty() {
+            ConfigurationRepr::Name(value.name.unwrap_or_default())
+        } else {
+            ConfigurationRepr::Overrides(value.overrides)
+        }
+    }
+}
+
+/// A workspace is a set of project directories which can all be built at once with `--workspace`.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Default, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Workspace {
+    /// The project directories, relative to the current working directory. Each member
+    /// can have its own manifest.
+    #[schemars(with = "Vec<String>")]
+    pub members: Vec<Utf8PathBuf>,
+}
+
+/// The names a project manifest can have, in the project's directory.
+pub const MANIFEST_NAMES: &[&str] = &[
+    ".adam",
+    "adam.toml",
+    ".adam.toml",
+    "adam.json",
+    ".adam.json",
+];
+
+/// The name of the developer's own manifest, which sits next to the project manifest and is
+/// applied over it. It's for settings like `gms2_install_location`, which differ between
+/// machines, so it shouldn't be committed.
+pub const LOCAL_MANIFEST_NAME: &str = ".adam.local.toml";
+
+/// The manifests found for a project.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProjectManifests {
+    /// The shared manifest, such as `.adam.toml`.
+    pub project: Option<Manifest>,
+
+    /// The developer's [`LOCAL_MANIFEST_NAME`].
+    pub local: Option<Manifest>,
+}
+
+impl ProjectManifests {
+    /// Applies the project manifest, and then the local manifest, on top of the other manifest.
+    pub fn apply_on(self, target_config: &mut Manifest) {
+        for manifest in [self.project, self.local].into_iter().flatten() {
+            manifest.apply_on(target_config);
+        }
+    }
+}
+
+/// Asks git if the file is ignored. Returns `None` if git isn't installed, or if the file is not
+/// in a git repository.
+fn is_git_ignored(path: &Path) -> Option<bool> {
+    let directory = path
+        .parent()
+        .filter(|v| v.as_os_str().is_empty() == false)?;
+    let status = std::process::Command::new("git")
+        .arg("check-ignore")
+        .arg("--quiet")
+        .arg(path.file_name()?)
+        .current_dir(directory)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .ok()?;
+
+    match status.code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
 }
 
 impl Manifest {
@@ -112,27 +323,56 @@ impl Manifest {
         #[allow(deprecated)]
         let Self {
             configuration,
+            yyc,
+            target,
+            yyp,
+            workspace,
             verbosity,
             output_folder,
             ignore_cache,
             gms2_install_location,
             beta,
             runtime,
+            runtime_feed,
             no_user_folder,
             runtime_location_override,
             visual_studio_path,
             user_license_folder,
+            jobs,
+            igor_args,
+            asset_compiler_args,
+            deny_warnings,
             test_env_variables,
             test_success_keyword,
             path_to_run_windows,
             path_to_run_nix,
+            compiler_markers,
             x64_windows: _,
+            profile: _,
+            inherits: _,
+            extends: _,
         } = self;
 
-        if let Some(o) = configuration {
+        if let Some(o) = configuration.name {
             run_options.task.config = o;
         }
 
+        if let Some(yyc) = yyc {
+            run_options.task.yyc = yyc;
+        }
+
+        if let Some(target) = target {
+            run_options.task.target = target;
+        }
+
+        if let Some(yyp) = yyp {
+            run_options.task.yyp = Some(yyp);
+        }
+
+        if let Some(workspace) = workspace {
+            run_options.task.workspace_members = workspace.members;
+        }
+
         if let Some(verb) = verbosity {
             run_options.task.verbosity = verb;
         }
@@ -154,7 +394,8 @@ impl Manifest {
                 DEFAULT_PLATFORM_DATA.beta_application_path.into();
 
             run_options.platform.runtime_location =
-                DEFAULT_PLATFORM_DATA.beta_runtime_location.into();
+                DEFAULT_PLATFORM_DATA.beta_runtime_location.clone();
+            run_options.platform.runtime_pinned = false;
 
             run_options.platform.compiler_cache = DEFAULT_PLATFORM_DATA.beta_cached_data.clone();
         }
@@ -167,10 +408,16 @@ impl Manifest {
                 .unwrap()
                 .join(format!("runtime-{}", runtime));
             run_options.platform.runtime_location = path;
+            run_options.platform.runtime_pinned = true;
         }
 
         if let Some(o) = runtime_location_override {
             run_options.platform.runtime_location = o;
+            run_options.platform.runtime_pinned = true;
+        }
+
+        if let Some(o) = runtime_feed {
+            run_options.platform.runtime_feed = o;
         }
 
         if let Some(o) = visual_studio_path {
@@ -181,17 +428,28 @@ impl Manifest {
             run_options.platform.user_license_folder = o;
         }
         run_options.task.no_user_folder = no_user_folder;
+        if let Some(jobs) = jobs {
+            run_options.task.jobs = jobs;
+        }
+        run_options.task.igor_args = igor_args;
+        run_options.task.asset_compiler_args = asset_compiler_args;
+        if let Some(deny_warnings) = deny_warnings {
+            run_options.task.deny_warnings = deny_warnings;
+        }
         run_options.task.test_env_variables = test_env_variables;
         if let Some(o) = test_success_keyword {
             run_options.task.test_success_needle = o;
         }
-        let target = if cfg!(target_os = "windows") {
+        let check_script = if cfg!(target_os = "windows") {
             path_to_run_windows
         } else {
             path_to_run_nix
         };
-        if let Some(target) = target {
-            *check_options = Some(target);
+        if let Some(check_script) = check_script {
+            *check_options = Some(check_script);
+        }
+        if let Some(compiler_markers) = compiler_markers {
+            run_options.task.compiler_markers = compiler_markers;
         }
     }
 
@@ -200,25 +458,61 @@ impl Manifest {
         #[allow(deprecated)]
         let Self {
             configuration,
+            yyc,
+            target,
+            yyp,
+            workspace,
             verbosity,
             output_folder,
             ignore_cache,
             gms2_install_location,
             beta,
             runtime,
+            runtime_feed,
             no_user_folder,
             runtime_location_override,
             visual_studio_path,
             user_license_folder,
+            jobs,
+            igor_args,
+            asset_compiler_args,
+            deny_warnings,
             mut test_env_variables,
             test_success_keyword,
             path_to_run_windows,
             path_to_run_nix,
+            compiler_markers,
             x64_windows: _,
+            profile,
+            inherits,
+            // these are resolved when the manifest is loaded
+            extends: _,
         } = self;
 
-        if let Some(o) = configuration {
-            target_config.configuration = Some(o);
+        if let Some(o) = configuration.name {
+            target_config.configuration.name = Some(o);
+        }
+
+        // overrides for the same configuration are replaced, not merged
+        target_config
+            .configuration
+            .overrides
+            .extend(configuration.overrides);
+
+        if let Some(yyc) = yyc {
+            target_config.yyc = Some(yyc);
+        }
+
+        if let Some(target) = target {
+            target_config.target = Some(target);
+        }
+
+        if let Some(yyp) = yyp {
+            target_config.yyp = Some(yyp);
+        }
+
+        if let Some(workspace) = workspace {
+            target_config.workspace = Some(workspace);
         }
 
         if let Some(verb) = verbosity {
@@ -249,6 +543,10 @@ impl Manifest {
             target_config.runtime_location_override = Some(o);
         }
 
+        if let Some(o) = runtime_feed {
+            target_config.runtime_feed = Some(o);
+        }
+
         if let Some(o) = visual_studio_path {
             target_config.visual_studio_path = Some(o);
         }
@@ -259,6 +557,14 @@ impl Manifest {
         if no_user_folder {
             target_config.no_user_folder = true;
         }
+        if let Some(jobs) = jobs {
+            target_config.jobs = Some(jobs);
+        }
+        crate::runner::merge_args(&mut target_config.igor_args, igor_args);
+        crate::runner::merge_args(&mut target_config.asset_compiler_args, asset_compiler_args);
+        if let Some(deny_warnings) = deny_warnings {
+            target_config.deny_warnings = Some(deny_warnings);
+        }
         target_config
             .test_env_variables
             .append(&mut test_env_variables);
@@ -274,43 +580,427 @@ impl Manifest {
         if let Some(nix_path) = path_to_run_nix {
             target_config.path_to_run_nix = Some(nix_path);
         }
+
+        if let Some(compiler_markers) = compiler_markers {
+            compiler_markers.apply_on(target_config.compiler_markers.get_or_insert_default());
+        }
+
+        // profiles with the same name are replaced, not merged
+        target_config.profile.extend(profile);
+
+        if let Some(o) = inherits {
+            target_config.inherits = Some(o);
+        }
+    }
+
+    /// Resolves `[profile.<name>]` into a single manifest, applying every profile it inherits
+    /// from first.
+    pub fn resolve_profile(&self, name: &str) -> AnyResult<Manifest> {
+        let mut chain: Vec<&str> = vec![];
+        let mut next = Some(name);
+
+        while let Some(name) = next {
+            if chain.contains(&name) {
+                chain.push(name);
+                color_eyre::eyre::bail!("profiles inherit in a cycle: {}", chain.join(" -> "));
+            }
+
+            let Some(profile) = self.profile.get(name) else {
+                let known: Vec<&str> = self.profile.keys().map(|v| v.as_str()).collect();
+                if known.is_empty() {
+                    color_eyre::eyre::bail!("no profile named `{}`. No profiles are defined", name);
+                }
+
+                color_eyre::eyre::bail!(
+                    "no profile named `{}`. Known profiles are: {}",
+                    name,
+                    known.join(", ")
+                );
+            };
+
+            if profile.profile.is_empty() == false {
+                color_eyre::eyre::bail!("profile `{}` cannot contain other profiles", name);
+            }
+
+            chain.push(name);
+            next = profile.inherits.as_deref();
+        }
+
+        let mut output = Manifest::default();
+        for name in chain.iter().rev() {
+            self.profile[*name].clone().apply_on(&mut output);
+        }
+        output.inherits = None;
+
+        Ok(output)
+    }
+
+    /// Returns the `[configuration.<name>]` overrides for the given Gms2 configuration, if
+    /// there are any.
+    pub fn resolve_configuration(&self, name: &str) -> AnyResult<Option<Manifest>> {
+        let Some(overrides) = self.configuration.overrides.get(name) else {
+            return Ok(None);
+        };
+
+        if overrides.configuration.is_empty() == false {
+            color_eyre::eyre::bail!(
+                "`[configuration.{}]` cannot set `configuration`, as it only applies when that configuration is built",
+                name
+            );
+        }
+
+        if overrides.profile.is_empty() == false || overrides.inherits.is_some() {
+            color_eyre::eyre::bail!(
+                "`[configuration.{}]` cannot contain profiles or `inherits`",
+                name
+            );
+        }
+
+        Ok(Some(overrides.clone()))
     }
 }
 
 impl Manifest {
-    pub fn find_manifest(user_supplied_path: Option<&PathBuf>) -> Option<Manifest> {
+    /// Finds the project manifest, either at the path given or in the current directory, and the
+    /// developer's [`LOCAL_MANIFEST_NAME`] next to it. Either may be missing, but an invalid
+    /// manifest is an error, unless `allow_invalid` is set, in which case we print the error and
+    /// ignore the manifest.
+    pub fn find_manifest(
+        user_supplied_path: Option<&PathBuf>,
+        allow_invalid: bool,
+    ) -> AnyResult<ProjectManifests> {
+        let current_directory = std::env::current_dir().expect("cannot work in current directory");
         let config_path = match user_supplied_path {
-            Some(path) => path.to_path_buf(),
-            None => {
-                let current_directory =
-                    std::env::current_dir().expect("cannot work in current directory");
-                let mut iterator = current_directory.read_dir().ok()?.flatten();
+            Some(path) => Some(path.to_path_buf()),
+            None => current_directory.read_dir().ok().and_then(|iterator| {
                 iterator
+                    .flatten()
                     .find(|entry| {
-                        entry.file_name().to_str().is_some_and(|file| {
-                            matches!(
-                                file,
-                                ".adam" | "adam.toml" | ".adam.toml" | "adam.json" | ".adam.json"
-                            )
-                        })
+                        entry
+                            .file_name()
+                            .to_str()
+                            .is_some_and(|file| MANIFEST_NAMES.contains(&file))
                     })
-                    .map(|entry| entry.path())?
+                    .map(|entry| entry.path())
+            }),
+        };
+
+        let load = |path: &Path| match Self::load(path) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if allow_invalid => {
+                adam_print!(
+                    "{}: {}. Ignoring...",
+                    console::style("configuration error").red(),
+                    e
+                );
+
+                Ok(None)
             }
+            Err(e) => Err(e),
         };
 
-        let config = std::fs::read_to_string(config_path).ok().and_then(|txt| {
-            toml::from_str(&txt)
-                .or_else(|_| serde_json::from_str(&txt))
-                .ok()
-        });
+        let project = match &config_path {
+            Some(path) => load(path)?,
+            None => None,
+        };
 
-        if config.is_none() {
-            println!(
-                "{}: could not deserialize configuration file. Ignoring...",
-                console::style("configuration error").red()
-            );
+        let local_path = config_path
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|v| v.as_os_str().is_empty() == false)
+            .unwrap_or(&current_directory)
+            .join(LOCAL_MANIFEST_NAME);
+        let local = if local_path.exists() {
+            if is_git_ignored(&local_path) == Some(false) {
+                adam_print!(
+                    "{}: `{}` is not git-ignored, so it could be committed. Add it to your `.gitignore`",
+                    console::style("adam warning").bright().yellow(),
+                    local_path.display()
+                );
+            }
+
+            load(&local_path)?
+        } else {
+            None
+        };
+
+        Ok(ProjectManifests { project, local })
+    }
+
+    /// Reads the manifest at the given path, merging in the manifests it `extends`.
+    pub fn load(path: &Path) -> AnyResult<Manifest> {
+        Self::load_extending(path, &mut vec![])
+    }
+
+    fn load_extending(path: &Path, chain: &mut Vec<PathBuf>) -> AnyResult<Manifest> {
+        let canonical = dunce::canonicalize(path)
+            .map_err(|_| color_eyre::eyre::eyre!("could not find `{}`", path.display()))?;
+
+        if chain.contains(&canonical) {
+            chain.push(canonical);
+            let cycle: Vec<_> = chain.iter().map(|v| v.display().to_string()).collect();
+            color_eyre::eyre::bail!("manifests extend in a cycle: {}", cycle.join(" -> "));
         }
+        chain.push(canonical.clone());
+
+        let txt = std::fs::read_to_string(&canonical)
+            .map_err(|e| color_eyre::eyre::eyre!("could not read `{}`: {}", path.display(), e))?;
+        let mut manifest = Self::parse(path, &txt)?;
+
+        let Some(extends) = manifest.extends.take() else {
+            return Ok(manifest);
+        };
+
+        let base_path = canonical.parent().unwrap_or(&canonical).join(extends);
+        let mut base = Self::load_extending(&base_path, chain)?;
+        manifest.apply_on(&mut base);
+
+        Ok(base)
+    }
+
+    /// Parses a manifest in the format its extension implies. Files without one, like `.adam`,
+    /// are json if they start with a `{`, and toml otherwise. Errors give the file, line, and
+    /// column of the problem.
+    pub fn parse(path: &Path, txt: &str) -> AnyResult<Manifest> {
+        let is_json = match path.extension().and_then(|v| v.to_str()) {
+            Some("json") => true,
+            Some("toml") => false,
+            _ => txt.trim_start().starts_with('{'),
+        };
+
+        if is_json {
+            serde_json::from_str(txt).map_err(|e| {
+                // serde_json puts the position on the end of the message, so we take it back off
+                let message = e.to_string();
+                let message = message
+                    .rsplit_once(" at line ")
+                    .map_or(message.as_str(), |(message, _)| message);
+
+                color_eyre::eyre::eyre!(
+                    "{}:{}:{}: {}",
+                    path.display(),
+                    e.line(),
+                    e.column(),
+                    message
+                )
+            })
+        } else {
+            toml::from_str(txt).map_err(|e| {
+                let (line, column) = e
+                    .span()
+                    .map(|span| line_and_column(txt, span.start))
+                    .unwrap_or((1, 1));
+
+                color_eyre::eyre::eyre!("{}:{}:{}: {}", path.display(), line, column, e.message())
+            })
+        }
+    }
+}
+
+/// Converts a byte offset into a 1-based line and column.
+fn line_and_column(txt: &str, offset: usize) -> (usize, usize) {
+    let before = &txt[..offset.min(txt.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit_once('\n')
+        .map_or(before, |(_, line)| line)
+        .chars()
+        .count()
+        + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(toml: &str) -> Manifest {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn profiles_apply_their_parents_first() {
+        let manifest = manifest(
+            r#"
+            [profile.debug]
+            configuration = "Debug"
+            verbosity = 1
+
+            [profile.ship]
+            inherits = "debug"
+            configuration = "Release"
+            runtime = "2024.6.0.205"
+            "#,
+        );
+
+        let ship = manifest.resolve_profile("ship").unwrap();
+        assert_eq!(ship.configuration.name.as_deref(), Some("Release"));
+        assert_eq!(ship.verbosity, Some(1));
+        assert_eq!(ship.runtime.as_deref(), Some("2024.6.0.205"));
+        assert_eq!(ship.inherits, None);
+    }
+
+    #[test]
+    fn profile_cycles_and_unknown_profiles_are_errors() {
+        let manifest = manifest(
+            r#"
+            [profile.a]
+            inherits = "b"
+
+            [profile.b]
+            inherits = "a"
+
+            [profile.c]
+            inherits = "missing"
+            "#,
+        );
+
+        let err = manifest.resolve_profile("a").unwrap_err().to_string();
+        assert!(err.contains("a -> b -> a"), "{}", err);
+        assert!(manifest.resolve_profile("c").is_err());
+        assert!(manifest.resolve_profile("nope").is_err());
+    }
+
+    #[test]
+    fn configuration_is_a_name_or_a_set_of_overrides() {
+        let named = manifest(r#"configuration = "Release""#);
+        assert_eq!(named.configuration.name.as_deref(), Some("Release"));
+
+        let overridden = manifest(
+            r#"
+            [configuration.Release]
+            yyc = true
+            test_success_keyword = "SHIPPED"
+            "#,
+        );
+        assert_eq!(overridden.configuration.name, None);
+
+        let release = overridden
+            .resolve_configuration("Release")
+            .unwrap()
+            .unwrap();
+        assert_eq!(release.yyc, Some(true));
+        assert_eq!(release.test_success_keyword.as_deref(), Some("SHIPPED"));
+        assert_eq!(overridden.resolve_configuration("Debug").unwrap(), None);
+
+        let mut merged = named;
+        overridden.apply_on(&mut merged);
+        assert_eq!(merged.configuration.name.as_deref(), Some("Release"));
+        assert!(merged.resolve_configuration("Release").unwrap().is_some());
+    }
+
+    #[test]
+    fn a_later_layer_can_turn_the_yyc_back_off() {
+        let mut config = manifest("yyc = true");
+        manifest("yyc = false").apply_on(&mut config);
+        assert_eq!(config.yyc, Some(false));
+
+        // a layer which doesn't mention it leaves it alone
+        manifest("verbosity = 1").apply_on(&mut config);
+        assert_eq!(config.yyc, Some(false));
+
+        let mut options = crate::default_run_options();
+        options.task.yyc = true;
+        config.write_to_options(&mut options, &mut None);
+        assert!(options.task.yyc == false);
+    }
+
+    #[test]
+    fn extends_chains_merge_bases_first_and_catch_cycles() {
+        let dir = std::env::temp_dir().join(format!("adam-extends-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shared")).unwrap();
+
+        std::fs::write(
+            dir.join("shared/root.toml"),
+            "verbosity = 2\ntest_env_variables = [\"ROOT\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("shared/base.toml"),
+            "extends = \"root.toml\"\nruntime = \"2024.6.0.205\"\nverbosity = 1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("adam.toml"),
+            "extends = \"shared/base.toml\"\nverbosity = 3\ntest_env_variables = [\"PROJECT\"]\n",
+        )
+        .unwrap();
+
+        let manifest = Manifest::load(&dir.join("adam.toml")).unwrap();
+        assert_eq!(manifest.verbosity, Some(3));
+        assert_eq!(manifest.runtime.as_deref(), Some("2024.6.0.205"));
+        assert_eq!(manifest.test_env_variables, ["ROOT", "PROJECT"]);
+        assert_eq!(manifest.extends, None);
+
+        std::fs::write(dir.join("shared/root.toml"), "extends = \"../adam.toml\"\n").unwrap();
+        let err = Manifest::load(&dir.join("adam.toml")).unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{}", err);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn local_manifest_applies_over_the_project_manifest() {
+        let dir = std::env::temp_dir().join(format!("adam-local-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("adam.toml"), "verbosity = 1\nyyp = \"Game\"\n").unwrap();
+        let manifests = Manifest::find_manifest(Some(&dir.join("adam.toml")), false).unwrap();
+        assert!(manifests.local.is_none());
+
+        std::fs::write(
+            dir.join(LOCAL_MANIFEST_NAME),
+            "verbosity = 2\ngms2_install_location = \"D:/Steam/GameMaker\"\n",
+        )
+        .unwrap();
+        let mut merged = Manifest::default();
+        Manifest::find_manifest(Some(&dir.join("adam.toml")), false)
+            .unwrap()
+            .apply_on(&mut merged);
+        assert_eq!(merged.verbosity, Some(2));
+        assert_eq!(merged.yyp.as_deref(), Some("Game"));
+        assert_eq!(
+            merged.gms2_install_location.as_deref(),
+            Some("D:/Steam/GameMaker".into())
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_problem() {
+        let err = Manifest::parse(
+            Path::new("adam.toml"),
+            "verbosity = 1\nconfigration = \"Release\"\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.starts_with("adam.toml:2:1: unknown field `configration`"),
+            "{}",
+            err
+        );
+
+        let err = Manifest::parse(Path::new(".adam.json"), "{\n  \"verbosity\": \"high\"\n}")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with(".adam.json:2:"), "{}", err);
+    }
+
+    #[test]
+    fn schema_covers_nested_tables_and_deprecated_keys() {
+        let schema = serde_json::to_value(schemars::schema_for!(Manifest)).unwrap();
+
+        let x64_windows = &schema["properties"]["x64_windows"];
+        assert_eq!(x64_windows["deprecated"], true);
+        assert!(schema.get("required").is_none(), "{}", schema);
 
-        config
+        assert_eq!(
+            schema["properties"]["profile"]["additionalProperties"]["$ref"],
+            "#/definitions/Manifest"
+        );
+        assert!(schema["definitions"]["Configuration"]["anyOf"].is_array());
+        assert!(schema["properties"]["output_folder"]["description"].is_string());
     }
 }
@@ -3,27 +3,42 @@
 #![deny(rust_2021_compatibility)]
 #![allow(clippy::assigning_clones)]
 
-#[cfg(not(any(target_os = "windows", target_os = "macos")))]
-compile_error!("we only support `windows` and `macos` targets!");
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+compile_error!("we only support `windows`, `macos`, and `linux` targets!");
+
+/// Set once we know the build writes JSON to stdout, so that our own errors and warnings go to
+/// stderr instead.
+static JSON_OUTPUT: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+macro_rules! adam_print {
+    ($($args:tt)*) => {
+        if crate::JSON_OUTPUT.load(std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($args)*);
+        } else {
+            println!($($args)*);
+        }
+    };
+}
 
 macro_rules! adam_error {
     ($msg:expr) => {
-        println!("{}: {}", console::style("error").bright().red(), $msg);
+        adam_print!("{}: {}", console::style("error").bright().red(), $msg);
     };
     ($($args:tt)*) => {
-        println!("{}: {}", console::style("error").bright().red(), format_args!($($args)*));
+        adam_print!("{}: {}", console::style("error").bright().red(), format_args!($($args)*));
     };
 }
 
 macro_rules! adam_warning {
     ($msg:expr) => {
-        println!("{}: {}", console::style("error").bright().yellow(), $msg);
+        adam_print!("{}: {}", console::style("error").bright().yellow(), $msg);
     };
     ($($args:tt)*) => {
-        println!("{}: {}", console::style("error").bright().yellow(), format_args!($($args)*));
+        adam_print!("{}: {}", console::style("error").bright().yellow(), format_args!($($args)*));
     };
 }
 
+use camino::Utf8PathBuf;
 use clap::Parser;
 use std::{io::BufRead, process::ExitCode};
 
@@ -33,171 +48,139 @@ mod igor;
 use igor::{OutputKind, TargetFolders};
 
 mod input;
-use input::{ClapOperation, UserConfigOptions};
+use input::ClapOperation;
 
 mod gm_artifacts;
 use gm_artifacts::DEFAULT_PLATFORM_DATA;
 
+#[cfg(not(target_os = "linux"))]
 mod project_editing;
 
 mod runner;
 use runner::{PlatformOptions, RunOptions, TaskOptions};
 
+mod runtime;
+
+mod doctor;
+
+mod stats;
+
+mod config;
+
+mod init;
+
+mod user_config;
+
 fn main() -> ExitCode {
     color_eyre::install().unwrap();
     let inputs = input::InputOpts::parse();
+    let cli_options = inputs.subcmd.build_options().cloned();
+    // the rest of the options aren't loaded yet, but this covers any errors while loading them
+    let json_flag = cli_options
+        .as_ref()
+        .and_then(|v| v.message_format)
+        .is_some_and(|v| v.is_json());
+    JSON_OUTPUT.store(json_flag, std::sync::atomic::Ordering::Relaxed);
 
     // we have a few things that aren't really about building projects,
     // because this app has grown!
     match inputs.subcmd {
-        ClapOperation::UserConfig(v) => match v {
-            UserConfigOptions::View => {
-                let config: input::Manifest = confy::load("adam", None).unwrap();
-
-                println!("{}", toml::to_string_pretty(&config).unwrap());
-                return ExitCode::SUCCESS;
-            }
-            UserConfigOptions::Path => {
-                println!(
-                    "{}",
-                    confy::get_configuration_file_path("adam", None)
-                        .unwrap()
-                        .display()
+        ClapOperation::UserConfig(request) => {
+            return user_config::user_config_request(request);
+        }
+        operation @ (ClapOperation::Folder(_)
+        | ClapOperation::Script(_)
+        | ClapOperation::Object(_)
+        | ClapOperation::Shader(_)
+        | ClapOperation::Edit(_)
+        | ClapOperation::Remove { .. }
+        | ClapOperation::Rename { .. }
+        | ClapOperation::Reserialize) => {
+            // yy-boss needs a `yy_typings::serialize_file`, which yy-typings doesn't have on linux
+            #[cfg(target_os = "linux")]
+            {
+                let _ = operation;
+                adam_error!(
+                    "{}\nPlease log a feature request at https://github.com/NPC-Studio/adam/issues",
+                    console::style("adam does not support editing projects on linux, yet.").bold()
                 );
-                return ExitCode::SUCCESS;
-            }
-
-            UserConfigOptions::Edit { name, value } => {
-                let value = match name.as_str() {
-                    "verbosity" | "ignore_cache" => {
-                        let v: usize = match value.parse() {
-                            Ok(v) => v,
-                            Err(e) => {
-                                adam_error!("invalid value: {:?}", e);
-
-                                return ExitCode::FAILURE;
-                            }
-                        };
-
-                        serde_json::Value::Number(v.into())
-                    }
-                    "beta" | "no_user_folder" => {
-                        let v: bool = match value.parse() {
-                            Ok(v) => v,
-                            Err(e) => {
-                                adam_error!("invalid value: {:?}", e);
 
-                                return ExitCode::FAILURE;
-                            }
-                        };
-
-                        serde_json::Value::Bool(v)
-                    }
-                    "test_env_variables" => {
-                        serde_json::Value::Array(vec![serde_json::Value::String(value)])
-                    }
-                    "x64_windows" => {
-                        adam_error!("`x64_windows` is deprecated");
-
-                        return ExitCode::FAILURE;
-                    }
-                    _ => serde_json::Value::String(value),
-                };
-
-                let mut config: input::Manifest = confy::load("adam", None).unwrap();
+                return ExitCode::FAILURE;
+            }
 
-                let json_flash = serde_json::json!({ name: value });
-                let edit = match serde_json::from_value::<input::Manifest>(json_flash) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        adam_error!("invalid input: {:?}", e);
-                        return ExitCode::FAILURE;
-                    }
+            // the options are only needed to know which yyp to edit
+            #[cfg(not(target_os = "linux"))]
+            {
+                let Some((options, _)) = load_run_options(
+                    inputs.manifest.as_ref(),
+                    inputs.allow_invalid_manifest,
+                    cli_options.as_ref(),
+                ) else {
+                    return ExitCode::FAILURE;
                 };
-                edit.apply_on(&mut config);
 
-                confy::store("adam", None, config).unwrap();
-                println!(
-                    "{}: user configuration has been saved.",
-                    console::style("success").green().bright(),
-                );
-                return ExitCode::SUCCESS;
+                return project_editing::edit_project(operation, options.task.yyp.as_deref());
             }
-        },
-        ClapOperation::Folder(vfs) => {
-            return project_editing::folder_request(vfs);
         }
-        ClapOperation::Script(data) => {
-            return project_editing::add_script(data);
-        }
-        ClapOperation::Object(data) => {
-            return project_editing::add_object(data);
+        ClapOperation::Runtime(request) => {
+            let Some((options, _)) = load_run_options(
+                inputs.manifest.as_ref(),
+                inputs.allow_invalid_manifest,
+                cli_options.as_ref(),
+            ) else {
+                return ExitCode::FAILURE;
+            };
+
+            return runtime::runtime_request(request, options);
         }
-        ClapOperation::Shader(data) => {
-            return project_editing::add_shader(data);
+        ClapOperation::Init(request) => {
+            let mut options = default_run_options();
+            load_user_config().write_to_options(&mut options, &mut None);
+
+            return init::init(request, options);
         }
-        ClapOperation::Edit(edit_manifest) => {
-            let current_dir = std::env::current_dir().unwrap();
-            let target_folder = camino::Utf8PathBuf::from_path_buf(
-                current_dir.join(
-                    edit_manifest
-                        .output_folder
-                        .as_deref()
-                        .unwrap_or_else(|| camino::Utf8Path::new("target")),
-                ),
-            )
-            .unwrap();
-
-            return project_editing::edit_manifest(
-                edit_manifest.asset_name,
-                edit_manifest.view,
-                &target_folder,
+        ClapOperation::Config(request) => {
+            return config::config_request(
+                request,
+                inputs.manifest.as_ref(),
+                inputs.allow_invalid_manifest,
             );
         }
-        ClapOperation::Remove { name } => {
-            return project_editing::remove(name);
+        ClapOperation::Doctor(build_options) => {
+            let Some((mut options, _)) = load_run_options(
+                inputs.manifest.as_ref(),
+                inputs.allow_invalid_manifest,
+                cli_options.as_ref(),
+            ) else {
+                return ExitCode::FAILURE;
+            };
+            build_options.write_to_options(&mut options);
+
+            return doctor::doctor(options);
         }
-        ClapOperation::Rename {
-            current_name,
-            new_name,
-        } => {
-            return project_editing::rename(current_name, new_name);
+        ClapOperation::Stats(request) => {
+            let Some((mut options, _)) = load_run_options(
+                inputs.manifest.as_ref(),
+                inputs.allow_invalid_manifest,
+                cli_options.as_ref(),
+            ) else {
+                return ExitCode::FAILURE;
+            };
+            request.build_options.write_to_options(&mut options);
+
+            return stats::stats(request.last, options);
         }
-        ClapOperation::Reserialize => return project_editing::reserialize(),
 
         _ => {}
     }
 
-    let mut config: input::Manifest = match confy::load("adam", None) {
-        Ok(v) => v,
-        Err(e) => {
-            adam_warning!("user-config was invalid ({}). replacing with default...", e,);
-
-            input::Manifest::default()
-        }
-    };
-    let patch_config = input::Manifest::find_manifest(inputs.manifest.as_ref()).unwrap_or_default();
-
-    patch_config.apply_on(&mut config);
-
-    let mut runtime_options = {
-        let platform: PlatformOptions = PlatformOptions {
-            gms2_application_location: DEFAULT_PLATFORM_DATA.stable_application_path.into(),
-            runtime_location: DEFAULT_PLATFORM_DATA.stable_runtime_location.into(),
-            visual_studio_path: Default::default(),
-            user_license_folder: Default::default(),
-            compiler_cache: DEFAULT_PLATFORM_DATA.stable_cached_data.clone(),
-        };
-        let task = TaskOptions::default();
-
-        RunOptions {
-            task,
-            platform,
-            no_compile: None,
-        }
+    let Some((runtime_options, mut script_path_to_run)) = load_run_options(
+        inputs.manifest.as_ref(),
+        inputs.allow_invalid_manifest,
+        cli_options.as_ref(),
+    ) else {
+        return ExitCode::FAILURE;
     };
-    let mut script_path_to_run = None;
-    config.write_to_options(&mut runtime_options, &mut script_path_to_run);
 
     let manifest_only_runtime_options = runtime_options.clone();
 
@@ -206,35 +189,67 @@ fn main() -> ExitCode {
             Ok(v) => v,
             Err(e) => {
                 adam_error!("parsing inputs: {}", e);
+                adam_print!("run `adam doctor` to check your setup");
                 return ExitCode::FAILURE;
             }
         };
 
+    JSON_OUTPUT.store(
+        options.task.message_format.is_json(),
+        std::sync::atomic::Ordering::Relaxed,
+    );
+
     if options.task.no_build_script {
         script_path_to_run = None;
     }
 
+    let problems = runner::check_build_args(
+        Some(options.task.jobs),
+        &options.task.igor_args,
+        &options.task.asset_compiler_args,
+    );
+    if problems.is_empty() == false {
+        for problem in problems {
+            adam_error!("{}", problem);
+        }
+
+        return ExitCode::FAILURE;
+    }
+
+    // each member will load its own manifest and user data, so we're done here.
+    if options.task.workspace {
+        return runner::run_workspace(&options.task.workspace_members);
+    }
+
+    let runtime_selection = runtime::resolve_current_project(&mut options);
+    if options.task.verbosity > 0 {
+        adam_print!(
+            "using runtime {} ({})",
+            runtime_selection.version,
+            runtime_selection.reason
+        );
+    }
+
     if let Err(e) = options.platform.canonicalize() {
         adam_error!(
             "invalid {} path (file does not exist). Is everything installed correctly?",
             console::style(e).bold()
         );
+        adam_print!("run `adam doctor` to check your setup");
 
         return ExitCode::FAILURE;
     }
 
-    if options.task.yyc {
-        if let Err(_e) = options.platform.canonicalize_yyc() {
-            adam_error!(
-                "invalid yyc path `{}` (file does not exist). Is everything installed correctly?",
-                console::style(options.platform.visual_studio_path).bold()
-            );
+    if options.task.yyc && options.platform.canonicalize_yyc().is_err() {
+        adam_error!(
+            "invalid yyc path `{}` (file does not exist). Is everything installed correctly?",
+            console::style(options.platform.visual_studio_path).bold()
+        );
 
-            return ExitCode::FAILURE;
-        }
+        return ExitCode::FAILURE;
     }
 
-    let application_data = match igor::ApplicationData::new() {
+    let application_data = match igor::ApplicationData::new(options.task.yyp.as_deref()) {
         Ok(v) => v,
         Err(e) => {
             adam_error!("{}", e);
@@ -255,15 +270,22 @@ fn main() -> ExitCode {
     // handle a clean, extract the build_data
     let run_kind = match operation {
         input::Operation::Run(inner) => {
-            if let Some(check_options) = script_path_to_run {
-                if runner::run_check(&options.task, check_options).is_err() {
-                    return ExitCode::FAILURE;
-                }
+            // a dry run doesn't run anything, including the check scripts
+            if let Some(check_options) = script_path_to_run
+                && options.task.dry_run == false
+                && runner::run_check(&options.task, check_options).is_err()
+            {
+                return ExitCode::FAILURE;
             }
             inner
         }
         input::Operation::Check => {
             let exit_code = if let Some(check_options) = script_path_to_run {
+                if options.task.dry_run {
+                    adam_print!("would run the check script `{}`", check_options);
+                    return ExitCode::SUCCESS;
+                }
+
                 if runner::run_check(&options.task, check_options).is_ok() {
                     ExitCode::SUCCESS
                 } else {
@@ -278,14 +300,19 @@ fn main() -> ExitCode {
             return exit_code;
         }
         input::Operation::Clean => {
+            let output_folder = application_data
+                .current_directory
+                .join(&options.task.output_folder);
+
             // no need to crash or show an error here. it's fine!
-            let exit_code = if options.task.output_folder.exists() {
+            let exit_code = if options.task.dry_run {
+                if options.task.output_folder.exists() {
+                    adam_print!("would remove `{}`", output_folder);
+                }
+                ExitCode::SUCCESS
+            } else if options.task.output_folder.exists() {
                 // clean up the output folder...
-                match std::fs::remove_dir_all(
-                    application_data
-                        .current_directory
-                        .join(&options.task.output_folder),
-                ) {
+                match std::fs::remove_dir_all(output_folder) {
                     Ok(()) => ExitCode::SUCCESS,
                     Err(e) => {
                         adam_error!("on clean: {}", e);
@@ -336,6 +363,7 @@ fn main() -> ExitCode {
         return run_no_compile(
             no_compile,
             &options,
+            &application_data.current_directory,
             application_data.project_name.as_deref(),
         );
     }
@@ -345,12 +373,18 @@ fn main() -> ExitCode {
         if cfg!(not(target_os = "windows")) {
             adam_error!(
                 "{}\nPlease log a feature request at https://github.com/NPC-Studio/adam/issues",
-                console::style("adam does not support macOS YYC compilation, yet.").bold(),
+                console::style(format!(
+                    "adam does not support {} YYC compilation, yet.",
+                    gm_artifacts::PLATFORM_KIND
+                ))
+                .bold(),
             );
             return ExitCode::FAILURE;
         }
 
-        if options.platform.visual_studio_path.exists() == false {
+        if options.task.target == igor::PlatformKind::Windows
+            && options.platform.visual_studio_path.exists() == false
+        {
             adam_error!(
                 "{}.\n\
             Supplied path in preferences was \"{}\" but it did not exist.\n\
@@ -381,6 +415,7 @@ fn main() -> ExitCode {
     let folders = match TargetFolders::new(
         &application_data.current_directory,
         &options.task.output_folder,
+        options.task.target,
         output_kind,
         &project_filename,
     ) {
@@ -400,7 +435,7 @@ fn main() -> ExitCode {
         user_dir: Default::default(),
         license_folder: options.platform.user_license_folder.clone(),
         runtime_location: options.platform.runtime_location.clone(),
-        target_mask: DEFAULT_PLATFORM_DATA.target_mask,
+        target: options.task.target,
         application_path: options.platform.gms2_application_location.clone(),
         config: options.task.config.clone(),
     };
@@ -410,6 +445,75 @@ fn main() -> ExitCode {
 
     let visual_studio_path = options.platform.visual_studio_path.clone();
 
+    // we can only run the last build's output ourselves for the windows vm. Igor starts the
+    // other runners, and the yyc's executable, so those always go through it
+    let can_run_cached = cfg!(target_os = "windows")
+        && options.task.dry_run == false
+        && options.task.target == igor::PlatformKind::Windows
+        && build_data.output_kind == OutputKind::Vm
+        && matches!(run_kind, input::RunKind::Run | input::RunKind::Test(_));
+
+    let message_format = options.task.message_format;
+    let started = std::time::Instant::now();
+    if message_format.is_json() && options.task.dry_run == false {
+        runner::Message::BuildStarted {
+            project: &macros.project_name,
+            kind: match run_kind {
+                input::RunKind::Run => "run",
+                input::RunKind::Build => "build",
+                input::RunKind::Release => "release",
+                input::RunKind::Test(_) => "test",
+            },
+            target: options.task.target.to_string(),
+            config: &options.task.config,
+            yyc: options.task.yyc,
+            runtime: &options.platform.runtime_location,
+        }
+        .emit();
+    }
+
+    // there's no use for a fingerprint unless we can skip Igor next time
+    let fingerprint = if can_run_cached && options.task.ignore_cache < 2 {
+        let previous = runner::Fingerprint::load(&build_data.folders.cache);
+        match runner::Fingerprint::new(&build_data.project_directory, &options, previous.as_ref()) {
+            Ok(fingerprint) => {
+                let data_win = build_data
+                    .folders
+                    .output
+                    .join(&build_data.project_filename)
+                    .with_extension("win");
+
+                if options.task.ignore_cache == 0
+                    && previous.is_some_and(|v| v.digest == fingerprint.digest)
+                    && data_win.exists()
+                {
+                    if message_format.is_json() == false {
+                        println!(
+                            "{} since the last build, so running it again",
+                            console::style("nothing has changed").dim()
+                        );
+                    }
+                    let success = runner::run_cached(&data_win, &macros, &options, &run_kind);
+
+                    return report_result(success, &run_kind, message_format, started);
+                }
+
+                Some(fingerprint)
+            }
+            Err(e) => {
+                adam_warning!("could not fingerprint the project because {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // the output is about to be replaced, so the old fingerprint no longer describes it
+    if options.task.dry_run == false {
+        runner::Fingerprint::remove(&build_data.folders.cache);
+    }
+
     // clear the temp files...
     if let Err(e) = build_data.folders.clear_tmp() {
         adam_error!("failed to make temp folder because {}", e);
@@ -461,21 +565,42 @@ fn main() -> ExitCode {
     )
     .unwrap();
 
-    // on macos, we don't have a good way to debug out, so this is the best we got.
+    // on unix, we don't have a good way to debug out, so this is the best we got.
+    #[cfg(not(target_os = "windows"))]
+    let socket_name = gm_build.temp_folder.join("ipc_log.log");
     #[cfg(not(target_os = "windows"))]
     {
-        use interprocess::local_socket::LocalSocketListener;
-
-        let socket_name = gm_build.temp_folder.join("ipc_log.log");
         // we are single-threaded, but this is sicko-mode
         unsafe {
             std::env::set_var("ADAM_IPC_SOCKET", socket_name.clone());
         }
+    }
+
+    if options.task.dry_run {
+        let command = runner::igor_command(&run_kind, &macros, &build_location, &options);
+        adam_print!("{}", runner::describe_command(&command, &options));
+        adam_print!(
+            "adam {}: wrote the build files to `{}`",
+            console::style("dry run").green().bright(),
+            build_data.folders.cache
+        );
+
+        return ExitCode::SUCCESS;
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        use interprocess::local_socket::LocalSocketListener;
 
         if let Ok(listener) = LocalSocketListener::bind(socket_name.as_std_path()) {
+            let scripts_directory = macros.project_dir.join("scripts");
+            let message_format = options.task.message_format;
+
             std::thread::Builder::new()
                 .name("adam-ipc".into())
                 .spawn(move || {
+                    let mut printer = runner::Printer::new(&scripts_directory, message_format);
+
                     for mut stream in listener.incoming().filter_map(|v| v.ok()) {
                         loop {
                             use std::io::Read;
@@ -495,7 +620,9 @@ fn main() -> ExitCode {
                             };
 
                             let str = std::str::from_utf8(&bytes).unwrap();
-                            print!("{}", str);
+                            for line in str.lines() {
+                                printer.print_line(line.to_owned());
+                            }
                         }
                     }
                 })
@@ -504,28 +631,114 @@ fn main() -> ExitCode {
     }
 
     let success = runner::run_command(&build_location, macros, options, &run_kind);
-    if success {
+    let saved = fingerprint
+        .filter(|_| success)
+        .map(|v| v.save(&build_data.folders.cache));
+    if let Some(Err(e)) = saved {
+        adam_warning!("could not save the build's fingerprint because {}", e);
+    }
+
+    report_result(success, &run_kind, message_format, started)
+}
+
+fn report_result(
+    success: bool,
+    run_kind: &input::RunKind,
+    message_format: runner::MessageFormat,
+    started: std::time::Instant,
+) -> ExitCode {
+    if message_format.is_json() {
         if run_kind.is_test() {
-            println!(
-                "adam test result: {}",
-                console::style("ok").green().bright()
-            );
+            runner::Message::TestResult { passed: success }.emit();
+        }
+        runner::Message::BuildFinished {
+            success,
+            duration_ms: started.elapsed().as_millis(),
+        }
+        .emit();
+    } else {
+        let word = if success {
+            console::style("ok").green().bright()
         } else {
+            console::style("FAILED").red().bright()
+        };
+
+        if run_kind.is_test() {
+            println!("adam test result: {}", word);
+        } else if success {
             println!("adam {}", console::style("complete").green().bright());
+        } else {
+            println!("adam {}", word);
         }
+    }
 
+    if success {
         ExitCode::SUCCESS
     } else {
-        if run_kind.is_test() {
-            println!(
-                "adam test result: {}",
-                console::style("FAILED").red().bright()
-            );
-        } else {
-            println!("adam {}", console::style("FAILED").red().bright());
+        ExitCode::FAILURE
+    }
+}
+
+/// Resolves the options from every layer of config. The CLI options are only used to pick the
+/// profile and configuration -- they still need to be written on afterwards.
+///
+/// If the manifest is invalid, or the profile or configuration can't be resolved, we print why
+/// and return `None`.
+fn load_run_options(
+    manifest: Option<&std::path::PathBuf>,
+    allow_invalid_manifest: bool,
+    cli_options: Option<&input::BuildOptions>,
+) -> Option<(RunOptions, Option<Utf8PathBuf>)> {
+    config::resolve_options(manifest, allow_invalid_manifest, cli_options)
+        .map(config::ResolvedOptions::into_parts)
+}
+
+/// Reads the `ADAM_*` environment variables, warning about any which aren't options.
+fn load_env_options() -> Option<input::EnvOptions> {
+    match input::EnvOptions::from_env() {
+        Ok(env) => {
+            for name in env.unknown.iter() {
+                adam_warning!("`{}` is not an adam option, so it was ignored", name);
+            }
+
+            Some(env)
         }
+        Err(e) => {
+            adam_error!("invalid environment variable: {:#}", e);
+            None
+        }
+    }
+}
 
-        ExitCode::FAILURE
+/// Loads the user config, replacing it with the default if it can't be read.
+fn load_user_config() -> input::Manifest {
+    match confy::load("adam", None) {
+        Ok(v) => v,
+        Err(e) => {
+            adam_warning!("user-config was invalid ({}). replacing with default...", e,);
+
+            input::Manifest::default()
+        }
+    }
+}
+
+/// The options we start with, before any config or flags are applied.
+fn default_run_options() -> RunOptions {
+    let platform: PlatformOptions = PlatformOptions {
+        gms2_application_location: DEFAULT_PLATFORM_DATA.stable_application_path.into(),
+        runtime_location: DEFAULT_PLATFORM_DATA.stable_runtime_location.clone(),
+        runtime_pinned: false,
+        runtime_feed: gm_artifacts::DEFAULT_RUNTIME_FEED.to_owned(),
+        visual_studio_path: Default::default(),
+        user_license_folder: Default::default(),
+        compiler_cache: DEFAULT_PLATFORM_DATA.stable_cached_data.clone(),
+    };
+    let task = TaskOptions::default();
+
+    RunOptions {
+        task,
+        platform,
+        no_compile: None,
     }
 }
 
@@ -533,6 +746,7 @@ fn main() -> ExitCode {
 fn run_no_compile(
     no_compile: &camino::Utf8Path,
     options: &RunOptions,
+    project_directory: &camino::Utf8Path,
     project_name: Option<&str>,
 ) -> ExitCode {
     let mut runner_command = std::process::Command::new(format!(
@@ -544,11 +758,15 @@ fn run_no_compile(
     let inferred = no_compile.as_str().is_empty();
 
     let data_win_path = if no_compile.as_str().is_empty() {
-        let sub_folder = options.task.output_folder.join(if options.task.yyc {
-            "yyc/output"
-        } else {
-            "vm/output"
-        });
+        let sub_folder = options
+            .task
+            .output_folder
+            .join(options.task.target.to_string())
+            .join(if options.task.yyc {
+                "yyc/output"
+            } else {
+                "vm/output"
+            });
 
         let last_bit = project_name.unwrap_or("data");
         sub_folder.join(last_bit).with_extension("win")
@@ -572,14 +790,22 @@ fn run_no_compile(
         .arg(data_win_path)
         .stdout(std::process::Stdio::piped());
 
-    if options.task.verbosity > 0 {
-        println!("{:?}", runner_command);
+    if options.task.dry_run || options.task.print_command {
+        adam_print!("{}", runner::describe_command(&runner_command, options));
+        if options.task.dry_run {
+            return ExitCode::SUCCESS;
+        }
+    } else if options.task.verbosity > 0 {
+        adam_print!("{:?}", runner_command);
     }
 
+    let message_format = options.task.message_format;
+    let mut printer = runner::Printer::new(&project_directory.join("scripts"), message_format);
+
     let mut child = runner_command.spawn().unwrap();
     let reader = std::io::BufReader::new(child.stdout.as_mut().unwrap()).lines();
     for line in reader.map_while(Result::ok) {
-        println!("{}", line.trim());
+        printer.print_line(line.trim().to_owned());
     }
 
     let success = match child.wait() {
@@ -587,12 +813,20 @@ fn run_no_compile(
         Err(_) => false,
     };
 
-    let (style_value, exit_code) = if success {
-        (console::style("ok").green().bright(), ExitCode::SUCCESS)
+    if message_format.is_json() {
+        runner::Message::TestResult { passed: success }.emit();
     } else {
-        (console::style("FAILED").red().bright(), ExitCode::FAILURE)
-    };
-    println!("adam test result: {}", style_value);
+        let style_value = if success {
+            console::style("ok").green().bright()
+        } else {
+            console::style("FAILED").red().bright()
+        };
+        println!("adam test result: {}", style_value);
+    }
 
-    exit_code
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
@@ -1,5 +1,5 @@
 mod script;
-use std::path::PathBuf;
+use std::{path::PathBuf, process::ExitCode};
 
 pub use script::add_script;
 
@@ -18,7 +18,38 @@ pub use folders::{folder_request, remove, rename};
 use yy_boss::YypBoss;
 use yy_typings::{ViewPath, ViewPathLocation};
 
-use crate::igor;
+use crate::{igor, input::ClapOperation};
+
+/// Runs one of the commands which edit the project, in the project picked by the `yyp` option.
+pub fn edit_project(operation: ClapOperation, yyp: Option<&str>) -> ExitCode {
+    match operation {
+        ClapOperation::Folder(vfs) => folder_request(vfs, yyp),
+        ClapOperation::Script(data) => add_script(data, yyp),
+        ClapOperation::Object(data) => add_object(data, yyp),
+        ClapOperation::Shader(data) => add_shader(data, yyp),
+        ClapOperation::Edit(request) => {
+            let current_dir = std::env::current_dir().unwrap();
+            let target_folder = camino::Utf8PathBuf::from_path_buf(
+                current_dir.join(
+                    request
+                        .output_folder
+                        .as_deref()
+                        .unwrap_or_else(|| camino::Utf8Path::new("target")),
+                ),
+            )
+            .unwrap();
+
+            edit_manifest(request.asset_name, request.view, &target_folder, yyp)
+        }
+        ClapOperation::Remove { name } => remove(name, yyp),
+        ClapOperation::Rename {
+            current_name,
+            new_name,
+        } => rename(current_name, new_name, yyp),
+        ClapOperation::Reserialize => reserialize(yyp),
+        _ => unreachable!("`{:?}` doesn't edit the project", operation),
+    }
+}
 
 fn maybe_find_vfs_path(yyp_boss: &YypBoss, input: Option<String>) -> Option<ViewPath> {
     match input {
@@ -46,10 +77,12 @@ fn find_vfs_path(yyp_boss: &YypBoss, vfs: &str) -> Option<ViewPath> {
     }
 }
 
+/// Loads the project picked by the `yyp` option, or the only one in the current directory.
 fn create_yyp_boss(
+    yyp: Option<&str>,
     mut make_yyp_boss: impl FnMut(PathBuf) -> Result<YypBoss, yy_boss::StartupError>,
 ) -> Option<yy_boss::YypBoss> {
-    let application_data = match igor::ApplicationData::new() {
+    let application_data = match igor::ApplicationData::new(yyp) {
         Ok(v) => v,
         Err(e) => {
             println!(
@@ -93,8 +126,8 @@ fn create_yyp_boss(
     }
 }
 
-fn create_yyp_boss_with_data() -> Option<yy_boss::YypBoss> {
-    let application_data = match igor::ApplicationData::new() {
+fn create_yyp_boss_with_data(yyp: Option<&str>) -> Option<yy_boss::YypBoss> {
+    let application_data = match igor::ApplicationData::new(yyp) {
         Ok(v) => v,
         Err(e) => {
             println!(
@@ -9,8 +9,9 @@ use yy_typings::{
 
 use crate::input::FolderRequest;
 
-pub fn folder_request(vfs: FolderRequest) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(|path_to_yyp| YypBoss::new(path_to_yyp, &[]))
+pub fn folder_request(vfs: FolderRequest, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) =
+        super::create_yyp_boss(yyp, |path_to_yyp| YypBoss::new(path_to_yyp, &[]))
     else {
         return ExitCode::FAILURE;
     };
@@ -96,8 +97,8 @@ pub fn folder_request(vfs: FolderRequest) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-pub fn remove(name: String) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss_with_data() else {
+pub fn remove(name: String, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss_with_data(yyp) else {
         return ExitCode::FAILURE;
     };
 
@@ -146,8 +147,8 @@ pub fn remove(name: String) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-pub fn rename(original: String, new_name: String) -> ExitCode {
-    let Some(mut yb) = super::create_yyp_boss_with_data() else {
+pub fn rename(original: String, new_name: String, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yb) = super::create_yyp_boss_with_data(yyp) else {
         return ExitCode::FAILURE;
     };
 
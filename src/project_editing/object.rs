@@ -7,8 +7,8 @@ use yy_typings::{CommonData, EventType, EventTypeConvertErrors, Object, ObjectEv
 
 use crate::input::ObjectEditRequest;
 
-pub fn add_object(request: ObjectEditRequest) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(|p| YypBoss::new(p, &[Resource::Object]))
+pub fn add_object(request: ObjectEditRequest, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss(yyp, |p| YypBoss::new(p, &[Resource::Object]))
     else {
         return ExitCode::FAILURE;
     };
@@ -168,21 +168,26 @@ pub fn add_object(request: ObjectEditRequest) -> ExitCode {
     ExitCode::SUCCESS
 }
 
-pub fn edit_manifest(name: String, view: bool, target_folder: &Utf8Path) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(|p| YypBoss::new(p, &[Resource::Object]))
+pub fn edit_manifest(
+    name: String,
+    view: bool,
+    target_folder: &Utf8Path,
+    yyp: Option<&str>,
+) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss(yyp, |p| YypBoss::new(p, &[Resource::Object]))
     else {
         return ExitCode::FAILURE;
     };
 
-    if let Some(resource_type) = yyp_boss.vfs.get_resource_type(&name) {
-        if resource_type != Resource::Object {
-            println!(
-                "{}: `{}` is not an Object. only objects are currently supported.",
-                "error".bright_red(),
-                name.bold()
-            );
-            return ExitCode::FAILURE;
-        }
+    if let Some(resource_type) = yyp_boss.vfs.get_resource_type(&name)
+        && resource_type != Resource::Object
+    {
+        println!(
+            "{}: `{}` is not an Object. only objects are currently supported.",
+            "error".bright_red(),
+            name.bold()
+        );
+        return ExitCode::FAILURE;
     }
 
     if yyp_boss.objects.get(&name).is_none() {
@@ -393,10 +398,10 @@ pub fn edit_manifest(name: String, view: bool, target_folder: &Utf8Path) -> Exit
         let _ = std::fs::remove_file(&path);
 
         // first, handle a rename
-        if request.name != name {
-            if let Err(e) = yyp_boss.rename_resource::<Object>(&name, request.name.clone()) {
-                println!("{}: couldn't rename because {}", "error".bright_red(), e)
-            }
+        if request.name != name
+            && let Err(e) = yyp_boss.rename_resource::<Object>(&name, request.name.clone())
+        {
+            println!("{}: couldn't rename because {}", "error".bright_red(), e)
         }
 
         let root_folder = yyp_boss.project_metadata().root_file.clone();
@@ -4,8 +4,8 @@ use camino::{Utf8Path, Utf8PathBuf};
 use colored::Colorize;
 use yy_boss::{Resource, YypBoss};
 
-pub fn reserialize() -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(|p| {
+pub fn reserialize(yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss(yyp, |p| {
         YypBoss::new(
             p,
             &[
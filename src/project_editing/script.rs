@@ -5,8 +5,8 @@ use yy_typings::{CommonData, Script};
 
 use crate::input::ScriptEditRequest;
 
-pub fn add_script(script: ScriptEditRequest) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(YypBoss::without_resources) else {
+pub fn add_script(script: ScriptEditRequest, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss(yyp, YypBoss::without_resources) else {
         return ExitCode::FAILURE;
     };
     yyp_boss
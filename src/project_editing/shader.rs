@@ -33,8 +33,8 @@ void main() {
 
 ";
 
-pub fn add_shader(shader: ShaderEditRequest) -> ExitCode {
-    let Some(mut yyp_boss) = super::create_yyp_boss(YypBoss::without_resources) else {
+pub fn add_shader(shader: ShaderEditRequest, yyp: Option<&str>) -> ExitCode {
+    let Some(mut yyp_boss) = super::create_yyp_boss(yyp, YypBoss::without_resources) else {
         return ExitCode::FAILURE;
     };
     yyp_boss
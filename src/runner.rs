@@ -1,14 +1,16 @@
 use crate::{gm_artifacts, input::RunKind};
-use std::process::Child;
+use std::process::{Child, Command};
 
 mod run;
-pub use run::run_command;
+pub use run::{run_cached, run_command};
 
 mod check_options;
 pub use check_options::run_check;
 
 mod compiler_handler;
+mod diagnostics;
 mod printer;
+pub use printer::Printer;
 mod run_options;
 
 pub use run_options::*;
@@ -16,6 +18,26 @@ pub use run_options::*;
 mod cache;
 pub use cache::Cache;
 
+mod extra_args;
+pub use extra_args::{check_build_args, merge_args};
+
+mod fingerprint;
+pub use fingerprint::Fingerprint;
+
+mod markers;
+pub use markers::{CompilerMarkers, MarkerSet};
+
+mod messages;
+pub use messages::{Message, MessageFormat, Stage};
+
+mod sarif;
+
+mod timings;
+pub use timings::{regressions, StageTimes, TimingHistory, TIMED_STAGES};
+
+mod workspace;
+pub use workspace::run_workspace;
+
 use camino::Utf8Path;
 
 pub fn invoke_igor(
@@ -24,12 +46,43 @@ pub fn invoke_igor(
     build_bff: &Utf8Path,
     run_options: &RunOptions,
 ) -> Child {
+    let mut command = igor_command(run_kind, macros, build_bff, run_options);
+    // json goes to stdout, so the command goes to stderr
+    let described = if run_options.task.print_command {
+        Some(describe_command(&command, run_options))
+    } else if run_options.task.verbosity > 1 {
+        Some(format!("{:?}", command))
+    } else {
+        None
+    };
+    if let Some(described) = described {
+        adam_print!("{}", described);
+    }
+
+    command.spawn().unwrap_or_else(|e| {
+        panic!(
+            "failed to spawn {} because {}",
+            command.get_program().to_string_lossy(),
+            e
+        )
+    })
+}
+
+/// Makes the Igor command for a build, or the GMAssetCompiler command for a bare Windows build,
+/// without spawning it.
+pub fn igor_command(
+    run_kind: &RunKind,
+    macros: &gm_artifacts::GmMacros,
+    build_bff: &Utf8Path,
+    run_options: &RunOptions,
+) -> Command {
     // we do all build operations directly with the Gmac
-    if *run_kind == RunKind::Build {
+    if *run_kind == RunKind::Build && run_options.task.target == crate::igor::PlatformKind::Windows
+    {
         #[cfg(target_os = "windows")]
         {
             use std::os::windows::process::CommandExt;
-            
+
             let cache = macros.asset_compiler_cache_directory.join("cache");
 
             let mut gmac = std::process::Command::new(macros.asset_compiler_path.clone());
@@ -38,7 +91,7 @@ pub fn invoke_igor(
                 .raw_arg("--zpex")
                 .raw_arg("--iv=0")
                 .raw_arg("--rv=0")
-                .raw_arg("-j=8")
+                .raw_arg(format!("-j={}", run_options.task.jobs))
                 .raw_arg(format!("--gn=\"{}\"", macros.project_name))
                 .raw_arg(format!("--td=\"{}\"", macros.temp_directory))
                 .raw_arg(format!("--cd=\"{}\"", cache))
@@ -63,10 +116,13 @@ pub fn invoke_igor(
                 .raw_arg("--rt=vm")
                 .raw_arg("--64bitgame=true");
 
-            return gmac
-                .stdout(std::process::Stdio::piped())
-                .spawn()
-                .expect("failed to spawn gmac process");
+            for arg in run_options.task.asset_compiler_args.iter() {
+                gmac.raw_arg(arg);
+            }
+
+            gmac.stdout(std::process::Stdio::piped());
+
+            return gmac;
         }
 
         #[cfg(target_family = "unix")]
@@ -80,22 +136,70 @@ pub fn invoke_igor(
     };
 
     let mut igor = std::process::Command::new(macros.igor_path.clone());
-    igor.arg("-j=8").arg(format!("-options={}", build_bff));
+    igor.arg(format!("-j={}", run_options.task.jobs))
+        .arg(format!("-options={}", build_bff));
 
     // add the verbosity
     if run_options.task.verbosity > 1 {
         igor.arg("-v");
     }
 
+    igor.args(run_options.task.igor_args.iter());
+
     // add the platform
     igor.arg("--")
-        .arg(gm_artifacts::PLATFORM_KIND.to_string())
+        .arg(run_options.task.target.to_string())
         .arg(word)
         .stdout(std::process::Stdio::piped());
 
-    if run_options.task.verbosity > 1 {
-        println!("{:?}", igor);
+    igor
+}
+
+/// Writes out a command as it would be run: the program and its arguments, and then the
+/// environment variables which adam set for it, or which it inherits from adam.
+pub fn describe_command(command: &Command, run_options: &RunOptions) -> String {
+    let quote = |v: &std::ffi::OsStr| {
+        let v = v.to_string_lossy();
+        if v.contains(' ') && v.contains('"') == false {
+            format!("\"{}\"", v)
+        } else {
+            v.into_owned()
+        }
+    };
+
+    let mut output = format!(
+        "{}:\n  {}",
+        console::style("command").bold(),
+        quote(command.get_program())
+    );
+    for arg in command.get_args() {
+        output.push(' ');
+        output.push_str(&quote(arg));
+    }
+
+    // igor inherits our environment, so we show the variables adam uses or sets
+    let mut env: Vec<(String, String)> = std::env::vars()
+        .filter(|(name, _)| {
+            name.starts_with(crate::input::ENV_PREFIX)
+                || run_options.task.test_env_variables.contains(name)
+        })
+        .collect();
+    for (name, value) in command.get_envs() {
+        let name = name.to_string_lossy().into_owned();
+        env.retain(|(v, _)| *v != name);
+        if let Some(value) = value {
+            env.push((name, value.to_string_lossy().into_owned()));
+        }
+    }
+    env.sort();
+
+    output.push_str(&format!("\n{}:", console::style("environment").bold()));
+    if env.is_empty() {
+        output.push_str(" none");
+    }
+    for (name, value) in env {
+        output.push_str(&format!("\n  {}={}", name, value));
     }
 
-    igor.spawn().unwrap()
+    output
 }
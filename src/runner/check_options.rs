@@ -31,27 +31,28 @@ pub fn run_check(task_options: &TaskOptions, path_to_run: Utf8PathBuf) -> Result
         .env("ADAM_CHECK", "1")
         .env("ADAM_YYC", if task_options.yyc { "1" } else { "0" })
         .env("ADAM_CONFIG", &task_options.config)
+        .env("ADAM_TARGET", task_options.target.to_string())
         .env("ADAM_VERBOSITY", task_options.verbosity.to_string())
         .env("ADAM_OUTPUT_FOLDER", &task_options.output_folder)
         .env("ADAM_IGNORE_CACHE", task_options.ignore_cache.to_string())
         .output()
         .expect("Failed to execute command");
 
-    if let Ok(value) = String::from_utf8(output.stderr) {
-        if !value.is_empty() {
-            print!("{value}");
-        }
+    if let Ok(value) = String::from_utf8(output.stderr)
+        && !value.is_empty()
+    {
+        adam_print!("{}", value.trim_end());
     }
-    if let Ok(value) = String::from_utf8(output.stdout) {
-        if !value.is_empty() {
-            print!("{value}");
-        }
+    if let Ok(value) = String::from_utf8(output.stdout)
+        && !value.is_empty()
+    {
+        adam_print!("{}", value.trim_end());
     }
 
     if output.status.success() {
         Ok(())
     } else {
-        println!(
+        adam_print!(
             "{}: check FAILED with {}",
             console::style("adam error").bright().red(),
             output.status
@@ -1,14 +1,22 @@
-use crate::{gm_artifacts, input::RunKind, RunOptions};
+use crate::{input::RunKind, RunOptions};
 
 use camino::Utf8Path;
 use heck::ToTitleCase;
-use indicatif::ProgressBar;
-use std::{io::BufRead, io::BufReader, process::Child};
+use indicatif::{HumanDuration, ProgressBar};
+use std::{io::BufRead, io::BufReader, process::Child, time::Duration};
 
-use super::Cache;
+use super::{
+    diagnostics::{self, CompileError},
+    timings::{StageClock, StageTimes},
+    Cache, CompilerMarkers, Stage,
+};
 
 pub struct CompilerHandler {
     state: CompilerState,
+    markers: CompilerMarkers,
+    warnings: Vec<String>,
+    estimate: Option<StageTimes>,
+    stage_times: Option<StageTimes>,
 }
 
 enum CompilerState {
@@ -19,20 +27,44 @@ enum CompilerState {
 }
 
 impl CompilerHandler {
-    pub fn new_run() -> Self {
+    pub fn new_run(markers: CompilerMarkers) -> Self {
         Self {
             state: CompilerState::Initialize,
+            markers,
+            warnings: vec![],
+            estimate: None,
+            stage_times: None,
         }
     }
 
-    pub fn new_re_run() -> Self {
+    pub fn new_re_run(markers: CompilerMarkers) -> Self {
         Self {
             state: CompilerState::PreRunToMainLoop(vec![]),
+            markers,
+            warnings: vec![],
+            estimate: None,
+            stage_times: None,
         }
     }
 
+    /// Sets how long we expect each stage to take, which the progress bar counts down from.
+    pub fn with_estimate(mut self, estimate: Option<StageTimes>) -> Self {
+        self.estimate = estimate;
+        self
+    }
+
+    /// How long each stage took, once the game has reached its main loop.
+    pub fn stage_times(&self) -> Option<StageTimes> {
+        self.stage_times
+    }
+
+    /// The lines of the GML compile which reported a warning.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn compile(
-        mut self,
+        &mut self,
         child: &mut Child,
         project_name: &str,
         project_path: &Utf8Path,
@@ -40,10 +72,21 @@ impl CompilerHandler {
         run_options: &RunOptions,
         cache: &Cache,
     ) -> CompilerOutput {
-        let progress_bar = ProgressBar::new(cache.time.as_secs());
+        let markers = &self.markers;
+        let message_format = run_options.task.message_format;
+
+        // json goes to stdout, so we can't draw over it
+        let progress_bar = if message_format.is_json() {
+            ProgressBar::hidden()
+        } else {
+            let expected = self.estimate.map(|v| v.total()).unwrap_or(cache.time);
+            ProgressBar::new(expected.as_secs())
+        };
         progress_bar.set_style(
             indicatif::ProgressStyle::default_bar()
-                .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {msg}")
+                .template(
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {prefix:.dim} {msg}",
+                )
                 .unwrap()
                 .progress_chars("#> "),
         );
@@ -58,21 +101,45 @@ impl CompilerHandler {
         let our_progress_bar = progress_bar.clone();
         std::thread::spawn(move || {
             let t = std::time::Instant::now();
+            let mut last_position = None;
             loop {
                 if our_progress_bar.is_finished() {
                     return;
                 }
 
+                // the length follows the estimate for the stages left, so this counts down
+                let position = t.elapsed().as_secs();
+                if last_position != Some(position) {
+                    last_position = Some(position);
+                    let left = our_progress_bar
+                        .length()
+                        .unwrap_or(0)
+                        .saturating_sub(position);
+                    our_progress_bar.set_prefix(if left > 0 {
+                        format!("~{} left", HumanDuration(Duration::from_secs(left)))
+                    } else {
+                        String::new()
+                    });
+                }
+
                 our_progress_bar.tick();
-                our_progress_bar.set_position(t.elapsed().as_secs());
+                our_progress_bar.set_position(position);
 
                 std::thread::sleep(std::time::Duration::new(0, 16666666));
             }
         });
 
         let start_time = std::time::Instant::now();
+        let mut clock = StageClock::new(self.estimate, progress_bar.clone(), message_format);
+        clock.enter(match self.state {
+            CompilerState::PreRunToMainLoop(_) => Stage::PreRun,
+            _ => Stage::Initialize,
+        });
         let lines = BufReader::new(child.stdout.as_mut().unwrap()).lines();
 
+        // every line since the last marker, in case we never see the next one
+        let mut unrecognized: Vec<String> = vec![];
+
         for line in lines.map_while(|v| v.ok()) {
             let max_size = line.len().min(30);
 
@@ -80,91 +147,137 @@ impl CompilerHandler {
                 CompilerState::Initialize => {
                     progress_bar.set_message(line[..max_size].to_string());
 
-                    if line.contains("[Compile]") {
+                    if CompilerMarkers::contains(&markers.compile_start, &line) {
                         self.state = CompilerState::Compile(vec![]);
+                        unrecognized.clear();
+                        clock.enter(Stage::Compile);
+                    } else {
+                        unrecognized.push(line);
                     }
                 }
                 CompilerState::Compile(e_msgs) => {
-                    if line.contains("Error") {
+                    if CompilerMarkers::contains(&markers.compile_error, &line) {
                         e_msgs.push(line);
                         progress_bar.set_message("Collecting errors...");
-                    } else if line.contains("Final Compile...finished") {
-                        if e_msgs.is_empty() {
+                    } else if CompilerMarkers::starts_with(&markers.compile_warning, &line) {
+                        self.warnings.push(line);
+                    } else if CompilerMarkers::contains(&markers.compile_end, &line) {
+                        if e_msgs.is_empty()
+                            && run_options.task.deny_warnings
+                            && self.warnings.is_empty() == false
+                        {
+                            progress_bar.finish_and_clear();
+
+                            return CompilerOutput::DeniedWarnings;
+                        } else if e_msgs.is_empty() {
                             self.state = CompilerState::ChunkBuilder;
+                            unrecognized.clear();
+                            clock.enter(Stage::ChunkBuilder);
                         } else {
-                            return CompilerOutput::Errors(e_msgs.clone());
+                            progress_bar.finish_and_clear();
+
+                            return CompilerOutput::Errors(diagnostics::parse_errors(
+                                e_msgs,
+                                project_path.parent().unwrap_or(project_path),
+                            ));
                         }
-                    } else if e_msgs.is_empty() {
-                        progress_bar.set_message(line[..max_size].to_string());
+                    } else {
+                        if e_msgs.is_empty() {
+                            progress_bar.set_message(line[..max_size].to_string());
+                        }
+                        unrecognized.push(line);
                     }
                 }
                 CompilerState::ChunkBuilder => {
-                    #[cfg(target_os = "windows")]
-                    const CHUNK_ENDER: &str = "Igor complete";
-
-                    #[cfg(not(target_os = "windows"))]
-                    const CHUNK_ENDER: &str = "Finished PrepareGame()";
-
                     // we're in the final stage...
-                    if line.contains(CHUNK_ENDER) {
+                    if CompilerMarkers::contains(&markers.chunk_end, &line) {
                         progress_bar.set_message("adam compile complete");
 
                         self.state = CompilerState::PreRunToMainLoop(vec![]);
+                        unrecognized.clear();
+                        clock.enter(Stage::PreRun);
                     } else {
                         progress_bar.set_message(line[..max_size].to_string());
+                        unrecognized.push(line);
                     }
                 }
                 CompilerState::PreRunToMainLoop(startup_msgs) => {
-                    const FINAL_EMITS: [&str; 11] = [
-                        "Run_Start",
-                        "CreateColPairs",
-                        "[Run]",
-                        "MainOptions.json",
-                        "gamepadcount",
-                        "hardware device",
-                        "Collision Event time",
-                        "Entering main loop.",
-                        "Total memory used",
-                        "Texture #",
-                        "********",
-                    ];
-
-                    if line == "Entering main loop." || line == "Igor complete." {
+                    if markers.main_loop.contains(&line) {
                         progress_bar.finish_and_clear();
+                        clock.enter(Stage::Run);
+                        self.stage_times = Some(clock.times());
 
-                        println!(
-                            "{} {} {} {}:{} in {}",
-                            console::style("Completed").green().bright(),
-                            gm_artifacts::PLATFORM_KIND,
-                            if run_options.task.yyc { "yyc" } else { "vm" },
-                            run_kind,
-                            console::style(&run_options.task.config).yellow().bright(),
-                            indicatif::HumanDuration(std::time::Instant::now() - start_time)
-                        );
+                        if message_format.is_json() == false {
+                            println!(
+                                "{} {} {} {}:{} in {}",
+                                console::style("Completed").green().bright(),
+                                run_options.task.target,
+                                if run_options.task.yyc { "yyc" } else { "vm" },
+                                run_kind,
+                                console::style(&run_options.task.config).yellow().bright(),
+                                HumanDuration(std::time::Instant::now() - start_time)
+                            );
+                        }
 
                         return CompilerOutput::SuccessAndRun(startup_msgs.clone());
                     } else {
                         // we're in the final stage...
-                        if FINAL_EMITS.iter().any(|&v| line.contains(v)) == false {
-                            startup_msgs.push(line);
+                        if CompilerMarkers::contains(&markers.startup_noise, &line) == false {
+                            startup_msgs.push(line.clone());
                         }
+                        unrecognized.push(line);
                     }
                 }
             }
         }
 
-        match self.state {
-            CompilerState::Compile(msgs) | CompilerState::PreRunToMainLoop(msgs) => {
-                CompilerOutput::Errors(msgs)
+        progress_bar.finish_and_clear();
+
+        let (marker, expected) = match &self.state {
+            // the errors are what we were looking for, so we don't need the next marker
+            CompilerState::Compile(msgs) if msgs.is_empty() == false => {
+                return CompilerOutput::Errors(diagnostics::parse_errors(
+                    msgs,
+                    project_path.parent().unwrap_or(project_path),
+                ));
+            }
+            // the build worked, so the game closed before it reached its main loop
+            CompilerState::PreRunToMainLoop(msgs) => {
+                return CompilerOutput::StartupFailed(msgs.clone());
             }
-            _ => CompilerOutput::Errors(vec!["adam error: unexpected end of compiler messages. \
-            Are you on an unsupported platform?"
-                .to_string()]),
+            CompilerState::Initialize => ("compile_start", &markers.compile_start),
+            CompilerState::Compile(_) => ("compile_end", &markers.compile_end),
+            CompilerState::ChunkBuilder => ("chunk_end", &markers.chunk_end),
+        };
+        // if igor failed, its own output says why. Otherwise, we missed a marker
+        if child.wait().is_ok_and(|v| v.success()) {
+            adam_print!(
+                "{}: Igor finished without printing a `{}` marker ({}). The runtime may have \
+                reworded it, so set `{}` in the manifest's `[compiler_markers]` to the line it \
+                prints now. Igor's output follows",
+                console::style("adam warning").bright().yellow(),
+                marker,
+                expected
+                    .iter()
+                    .map(|v| format!("`{}`", v))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                marker
+            );
         }
+
+        CompilerOutput::Unrecognized(unrecognized)
     }
 }
 
 pub enum CompilerOutput {
-    Errors(Vec<String>),
+    Errors(Vec<CompileError>),
     SuccessAndRun(Vec<String>),
+    /// The compile gave warnings, and `deny_warnings` is set, so we stopped before the game ran.
+    DeniedWarnings,
+    /// The game closed while it was starting up, so these are the lines it printed until then.
+    StartupFailed(Vec<String>),
+    /// Igor finished before we saw the markers we were waiting for, so these are its lines since
+    /// the last marker we did see. Whether the build worked is up to Igor's exit code.
+    Unrecognized(Vec<String>),
 }
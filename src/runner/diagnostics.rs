@@ -0,0 +1,530 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use gml_log_parser::ScriptMappings;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{
+    messages::{Message, MessageFormat},
+    printer::Printer,
+};
+
+/// `gml_Object_obj_player_Step_0(12) : message`, or `gml_Script_scr_move(4:9) : message`.
+static SYMBOL_ERROR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:(?:Error|Warning)\s*:\s*)?(?P<symbol>gml_(?:Object|Script)_\S+?)\s*\((?:line\s*)?(?P<line>\d+)(?:\s*[:,]\s*(?P<column>\d+))?\)\s*:\s*(?P<message>.*)$",
+    )
+    .unwrap()
+});
+
+/// `Object: obj_player Event: Step at line 12 : message`.
+static OBJECT_ERROR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:(?:Error|Warning)\s*:\s*)?Object:\s*(?P<object>\S+)\s+Event:\s*(?P<event>.+?)\s+at line\s+(?P<line>\d+)\s*:\s*(?P<message>.*)$",
+    )
+    .unwrap()
+});
+
+/// `Script: scr_move at line 4 : message`.
+static SCRIPT_ERROR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^(?:(?:Error|Warning)\s*:\s*)?Script:\s*(?P<script>\S+)\s+at line\s+(?P<line>\d+)\s*:\s*(?P<message>.*)$",
+    )
+    .unwrap()
+});
+
+/// An error code, such as `GM1009`, at the start of a message.
+static ERROR_CODE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(?:error|warning)\s+)?(?P<code>GM\d+)\s*:?\s*(?P<message>.*)$").unwrap()
+});
+
+/// Whether a line of the compile reported an error or a warning.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+
+    fn style<D>(self, text: D) -> console::StyledObject<D> {
+        match self {
+            Level::Error => console::style(text).red().bright().bold(),
+            Level::Warning => console::style(text).yellow().bright().bold(),
+        }
+    }
+}
+
+/// A line of the compile's output which reported an error or a warning.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(untagged)]
+pub enum CompileError {
+    Diagnostic(Diagnostic),
+    /// A line we couldn't parse, which we show as it is.
+    Raw(String),
+}
+
+/// A GML compile error, and where it is in the project.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Diagnostic {
+    /// The object or script, such as `obj_player`.
+    pub asset: String,
+    /// The object's event, such as `Step_0`.
+    pub event: Option<String>,
+    /// The file, relative to the project directory, if we could find it.
+    pub path: Option<Utf8PathBuf>,
+    /// The line in the file, starting from 1.
+    pub line: usize,
+    /// The column in the line, starting from 1, if the compiler gave one.
+    pub column: Option<usize>,
+    pub message: String,
+    /// The compiler's code for the error, such as `GM1009`.
+    pub code: Option<String>,
+    /// The line as the compiler printed it.
+    #[serde(skip)]
+    pub text: String,
+}
+
+impl CompileError {
+    /// Parses a line of the compile's output. `project_directory` is used to find object events,
+    /// and `scripts` to find the file a function is in.
+    pub fn parse(line: &str, project_directory: &Utf8Path, scripts: &ScriptMappings) -> Self {
+        let trimmed = line.trim();
+
+        let diagnostic = if let Some(captures) = SYMBOL_ERROR.captures(trimmed) {
+            let symbol = &captures["symbol"];
+            let line_number = &captures["line"];
+
+            let path = gml_log_parser::parse_log(&format!("{}:{}", symbol, line_number), scripts)
+                .ok()
+                .and_then(|v| {
+                    // the log parser gives us `path:line:column`
+                    let mut parts = v.rsplitn(3, ':');
+                    parts.nth(2).map(Utf8PathBuf::from)
+                });
+            let (asset, event) = match &path {
+                Some(path) if path.starts_with("objects") => (
+                    path.parent()
+                        .and_then(|v| v.file_name())
+                        .unwrap_or_default()
+                        .to_owned(),
+                    path.file_stem().map(|v| v.to_owned()),
+                ),
+                Some(path) => (path.file_stem().unwrap_or_default().to_owned(), None),
+                None => (
+                    symbol
+                        .trim_start_matches("gml_Object_")
+                        .trim_start_matches("gml_Script_")
+                        .to_owned(),
+                    None,
+                ),
+            };
+
+            Some((
+                asset,
+                event,
+                path,
+                line_number.to_owned(),
+                captures.name("column").map(|v| v.as_str().to_owned()),
+                captures["message"].to_owned(),
+            ))
+        } else if let Some(captures) = OBJECT_ERROR.captures(trimmed) {
+            let object = captures["object"].to_owned();
+            let event = object_event(project_directory, &object, &captures["event"]);
+            let path = event
+                .as_ref()
+                .map(|event| Utf8PathBuf::from(format!("objects/{}/{}.gml", object, event)));
+
+            Some((
+                object,
+                event.or_else(|| Some(captures["event"].to_owned())),
+                path,
+                captures["line"].to_owned(),
+                None,
+                captures["message"].to_owned(),
+            ))
+        } else {
+            SCRIPT_ERROR.captures(trimmed).map(|captures| {
+                let script = captures["script"].to_owned();
+                let path = Utf8PathBuf::from(format!("scripts/{}/{}.gml", script, script));
+                let path = project_directory.join(&path).exists().then_some(path);
+
+                (
+                    script,
+                    None,
+                    path,
+                    captures["line"].to_owned(),
+                    None,
+                    captures["message"].to_owned(),
+                )
+            })
+        };
+
+        let Some((asset, event, path, line_number, column, message)) = diagnostic else {
+            return CompileError::Raw(line.to_owned());
+        };
+        let Ok(line_number) = line_number.parse() else {
+            return CompileError::Raw(line.to_owned());
+        };
+
+        let (code, message) = match ERROR_CODE.captures(message.trim()) {
+            Some(captures) => (
+                Some(captures["code"].to_owned()),
+                captures["message"].to_owned(),
+            ),
+            None => (None, message.trim().to_owned()),
+        };
+
+        CompileError::Diagnostic(Diagnostic {
+            asset,
+            event,
+            path,
+            line: line_number,
+            column: column.and_then(|v| v.parse().ok()),
+            message,
+            code,
+            text: line.to_owned(),
+        })
+    }
+}
+
+impl Diagnostic {
+    /// Writes the diagnostic on one line, as `path:line:column: level[code]: message`, with the
+    /// path relative to the project directory. The column and code are left out when we don't
+    /// have them. Returns `None` if we couldn't find the file.
+    pub fn problem_line(&self, level: &str) -> Option<String> {
+        let path = self.path.as_ref()?;
+
+        let mut output = format!("{}:{}", path.as_str().replace('\\', "/"), self.line);
+        if let Some(column) = self.column {
+            output.push_str(&format!(":{}", column));
+        }
+        output.push_str(&format!(": {}", level));
+        if let Some(code) = &self.code {
+            output.push_str(&format!("[{}]", code));
+        }
+        output.push_str(&format!(": {}", self.message));
+
+        Some(output)
+    }
+}
+
+/// Finds the file for an object's event, such as `Step_0` for `Step`. Returns `None` unless
+/// exactly one file matches.
+fn object_event(project_directory: &Utf8Path, object: &str, event: &str) -> Option<String> {
+    let prefix = format!("{}_", event.replace(' ', "_"));
+
+    let mut matches = project_directory
+        .join("objects")
+        .join(object)
+        .read_dir_utf8()
+        .ok()?
+        .flatten()
+        .filter(|v| v.path().extension() == Some("gml"))
+        .filter_map(|v| v.path().file_stem().map(|v| v.to_owned()))
+        .filter(|v| v.starts_with(&prefix));
+
+    let first = matches.next()?;
+    matches.next().is_none().then_some(first)
+}
+
+/// Parses the lines which reported errors, removing any repeats.
+pub fn parse_errors(lines: &[String], project_directory: &Utf8Path) -> Vec<CompileError> {
+    let scripts = ScriptMappings::from_path(project_directory.join("scripts"));
+
+    let mut errors: Vec<CompileError> = vec![];
+    for line in lines {
+        let error = CompileError::parse(line, project_directory, &scripts);
+        if errors.contains(&error) == false {
+            errors.push(error);
+        }
+    }
+
+    errors
+}
+
+/// Prints each diagnostic like rustc does, with the line of source it points to.
+fn print_diagnostics(
+    diagnostics: &[CompileError],
+    level: Level,
+    project_directory: &Utf8Path,
+    printer: &mut Printer,
+    message_format: MessageFormat,
+) {
+    for diagnostic in diagnostics {
+        match diagnostic {
+            _ if message_format.is_json() => Message::diagnostic(diagnostic, level).emit(),
+            CompileError::Diagnostic(diagnostic) if message_format.is_short() => {
+                match diagnostic.problem_line(level.as_str()) {
+                    Some(line) => println!("{}", line),
+                    None => println!("{}", diagnostic.text.trim()),
+                }
+            }
+            CompileError::Diagnostic(diagnostic) => {
+                println!("{}", render(diagnostic, level, project_directory));
+            }
+            CompileError::Raw(line) => printer.print_line(line.clone()),
+        }
+    }
+}
+
+/// Prints each error, and then the count.
+pub fn print_errors(
+    errors: &[CompileError],
+    project_name: &str,
+    project_directory: &Utf8Path,
+    printer: &mut Printer,
+    message_format: MessageFormat,
+) {
+    print_diagnostics(
+        errors,
+        Level::Error,
+        project_directory,
+        printer,
+        message_format,
+    );
+
+    if message_format.is_json() == false {
+        println!(
+            "{}: could not compile `{}` due to {} {}",
+            Level::Error.style("error"),
+            project_name,
+            errors.len(),
+            if errors.len() == 1 { "error" } else { "errors" }
+        );
+    }
+}
+
+/// Prints each warning, and then the count. When `deny_warnings` is set, the count is an error.
+pub fn print_warnings(
+    warnings: &[CompileError],
+    project_name: &str,
+    project_directory: &Utf8Path,
+    printer: &mut Printer,
+    message_format: MessageFormat,
+    denied: bool,
+) {
+    print_diagnostics(
+        warnings,
+        Level::Warning,
+        project_directory,
+        printer,
+        message_format,
+    );
+    print_warning_summary(warnings.len(), project_name, message_format, denied);
+}
+
+/// Prints how many warnings the compile gave, unless there were none.
+pub fn print_warning_summary(
+    count: usize,
+    project_name: &str,
+    message_format: MessageFormat,
+    denied: bool,
+) {
+    if count == 0 || message_format.is_json() {
+        return;
+    }
+
+    let warnings = if count == 1 { "warning" } else { "warnings" };
+    if denied {
+        println!(
+            "{}: could not compile `{}` due to {} {}, because `deny_warnings` is set",
+            Level::Error.style("error"),
+            project_name,
+            count,
+            warnings
+        );
+    } else {
+        println!(
+            "{}: `{}` generated {} {}",
+            Level::Warning.style("warning"),
+            project_name,
+            count,
+            warnings
+        );
+    }
+}
+
+/// Writes out the diagnostic with its source, such as:
+///
+/// ```text
+/// error[GM1009]: malformed assignment statement
+///  --> objects/obj_player/Step_0.gml:4:7
+///   |
+/// 4 |     x = = 3;
+///   |       ^
+/// ```
+fn render(diagnostic: &Diagnostic, level: Level, project_directory: &Utf8Path) -> String {
+    let title = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", level.as_str(), code),
+        None => level.as_str().to_owned(),
+    };
+    let mut output = format!(
+        "{}: {}",
+        level.style(title),
+        console::style(&diagnostic.message).bold()
+    );
+
+    let gutter = " ".repeat(diagnostic.line.to_string().len());
+    let bar = console::style("|").blue().bright().bold();
+
+    let location = match &diagnostic.path {
+        Some(path) => match diagnostic.column {
+            Some(column) => format!("{}:{}:{}", path, diagnostic.line, column),
+            None => format!("{}:{}", path, diagnostic.line),
+        },
+        None => match &diagnostic.event {
+            Some(event) => format!("{} {} line {}", diagnostic.asset, event, diagnostic.line),
+            None => format!("{} line {}", diagnostic.asset, diagnostic.line),
+        },
+    };
+    output.push_str(&format!(
+        "\n{}{} {}",
+        gutter,
+        console::style("-->").blue().bright().bold(),
+        location
+    ));
+
+    let source = diagnostic.path.as_ref().and_then(|path| {
+        let source = std::fs::read_to_string(project_directory.join(path)).ok()?;
+        source
+            .lines()
+            .nth(diagnostic.line.checked_sub(1)?)
+            .map(|v| v.trim_end().to_owned())
+    });
+    if let Some(source) = source {
+        // tabs are shown as four spaces, so the caret has to count them that way too
+        let width_of = |v: &str| {
+            v.chars()
+                .map(|c| if c == '\t' { 4 } else { 1 })
+                .sum::<usize>()
+        };
+        let indent = width_of(&source[..source.len() - source.trim_start().len()]);
+        let (start, width) = match diagnostic.column {
+            Some(column) => {
+                let before: String = source.chars().take(column.saturating_sub(1)).collect();
+                (width_of(&before), 1)
+            }
+            None => (indent, width_of(&source) - indent),
+        };
+        let source = source.replace('\t', "    ");
+
+        output.push_str(&format!("\n{} {}", gutter, bar));
+        output.push_str(&format!(
+            "\n{} {} {}",
+            console::style(diagnostic.line).blue().bright().bold(),
+            bar,
+            source
+        ));
+        output.push_str(&format!(
+            "\n{} {} {}{}",
+            gutter,
+            bar,
+            " ".repeat(start),
+            level.style("^".repeat(width.max(1)))
+        ));
+    }
+
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_compile_errors() {
+        let scripts = ScriptMappings::new(
+            [("scr_move", "scr_move"), ("move_towards", "scr_move")]
+                .into_iter()
+                .map(|(a, b)| (a.to_owned(), b.to_owned()))
+                .collect(),
+        );
+        let project = Utf8Path::new("/not/a/project");
+
+        assert_eq!(
+            CompileError::parse(
+                "Error : gml_Object_obj_player_Step_0(12) : malformed assignment statement",
+                project,
+                &scripts
+            ),
+            CompileError::Diagnostic(Diagnostic {
+                asset: "obj_player".to_owned(),
+                event: Some("Step_0".to_owned()),
+                path: Some("objects/obj_player/Step_0.gml".into()),
+                line: 12,
+                column: None,
+                message: "malformed assignment statement".to_owned(),
+                code: None,
+                text: "Error : gml_Object_obj_player_Step_0(12) : malformed assignment statement"
+                    .to_owned(),
+            })
+        );
+
+        assert_eq!(
+            CompileError::parse(
+                "gml_Script_move_towards(4:9) : GM1009: unexpected symbol",
+                project,
+                &scripts
+            ),
+            CompileError::Diagnostic(Diagnostic {
+                asset: "scr_move".to_owned(),
+                event: None,
+                path: Some("scripts/scr_move/scr_move.gml".into()),
+                line: 4,
+                column: Some(9),
+                message: "unexpected symbol".to_owned(),
+                code: Some("GM1009".to_owned()),
+                text: "gml_Script_move_towards(4:9) : GM1009: unexpected symbol".to_owned(),
+            })
+        );
+
+        let CompileError::Diagnostic(diagnostic) = CompileError::parse(
+            "Object: obj_enemy Event: Create at line 3 : got '}' expected ')'",
+            project,
+            &scripts,
+        ) else {
+            panic!("object errors should parse");
+        };
+        assert_eq!(diagnostic.asset, "obj_enemy");
+        assert_eq!(diagnostic.event.as_deref(), Some("Create"));
+        assert_eq!(diagnostic.path, None);
+        assert_eq!(diagnostic.line, 3);
+        assert_eq!(diagnostic.problem_line("error"), None);
+
+        let CompileError::Diagnostic(diagnostic) = CompileError::parse(
+            "gml_Script_move_towards(4:9) : GM1009: unexpected symbol",
+            project,
+            &scripts,
+        ) else {
+            panic!("script errors should parse");
+        };
+        assert_eq!(
+            diagnostic.problem_line("error").as_deref(),
+            Some("scripts/scr_move/scr_move.gml:4:9: error[GM1009]: unexpected symbol")
+        );
+
+        let CompileError::Diagnostic(diagnostic) = CompileError::parse(
+            "Warning : gml_Script_move_towards(9) : GM2016: unused variable",
+            project,
+            &scripts,
+        ) else {
+            panic!("warnings should parse");
+        };
+        assert_eq!(
+            diagnostic.problem_line(Level::Warning.as_str()).as_deref(),
+            Some("scripts/scr_move/scr_move.gml:9: warning[GM2016]: unused variable")
+        );
+
+        assert_eq!(
+            CompileError::parse("Error: something went wrong", project, &scripts),
+            CompileError::Raw("Error: something went wrong".to_owned())
+        );
+    }
+}
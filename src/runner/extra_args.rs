@@ -0,0 +1,141 @@
+/// The flags adam passes to Igor itself. `--` ends Igor's options, so it can't be passed either.
+const IGOR_FLAGS: &[&str] = &["-j", "-options", "-v", "--"];
+
+/// The flags adam passes to GMAssetCompiler for a bare Windows build.
+const ASSET_COMPILER_FLAGS: &[&str] = &[
+    "-c",
+    "--mv",
+    "--zpex",
+    "--iv",
+    "--rv",
+    "-j",
+    "--gn",
+    "--td",
+    "--cd",
+    "--rtp",
+    "--zpuf",
+    "--prefabs",
+    "/ffe",
+    "-m",
+    "--tgt",
+    "--nodnd",
+    "--cfg",
+    "-o",
+    "-sh",
+    "--cvm",
+    "--baseproject",
+    "--debug",
+    "--bt",
+    "--rt",
+    "--64bitgame",
+];
+
+/// Adds the extra arguments of a later layer of config to those of the earlier layers. A flag in
+/// the later layer replaces the same flag from the earlier layers, so that a profile or the CLI
+/// can change a flag which the manifest sets.
+pub fn merge_args(target: &mut Vec<String>, args: Vec<String>) {
+    target.retain(|old| args.iter().all(|new| flag_name(new) != flag_name(old)));
+    target.extend(args);
+}
+
+/// Checks the job count and the extra arguments for Igor and GMAssetCompiler, returning a message
+/// for each problem. An extra argument can't repeat a flag which adam already passes, or which
+/// was already given in the same layer of config, since the tools don't say which one wins.
+pub fn check_build_args(
+    jobs: Option<u32>,
+    igor_args: &[String],
+    asset_compiler_args: &[String],
+) -> Vec<String> {
+    let mut problems = vec![];
+
+    if jobs == Some(0) {
+        problems.push("`jobs` must be at least 1".to_owned());
+    }
+
+    for (key, args, reserved) in [
+        ("igor_args", igor_args, IGOR_FLAGS),
+        (
+            "asset_compiler_args",
+            asset_compiler_args,
+            ASSET_COMPILER_FLAGS,
+        ),
+    ] {
+        let mut seen: Vec<&str> = vec![];
+
+        for arg in args {
+            let flag = flag_name(arg);
+
+            if reserved.contains(&flag) {
+                let hint = match flag {
+                    "-j" => ". Use `jobs` instead",
+                    "-v" => ". Use `verbosity` instead",
+                    _ => "",
+                };
+                problems.push(format!(
+                    "`{}` contains `{}`, but adam already passes `{}`{}",
+                    key, arg, flag, hint
+                ));
+            } else if seen.contains(&flag) {
+                problems.push(format!("`{}` passes `{}` more than once", key, flag));
+            } else {
+                seen.push(flag);
+            }
+        }
+    }
+
+    problems
+}
+
+/// The name of a flag, without its value, so that `--rt=vm` is `--rt`.
+fn flag_name(arg: &str) -> &str {
+    let arg = arg.trim();
+
+    arg.split_once('=').map(|(name, _)| name).unwrap_or(arg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_args_cannot_repeat_flags() {
+        let args = |v: &[&str]| v.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        assert!(check_build_args(Some(4), &args(&["-showtimings"]), &args(&["--x=1"])).is_empty());
+
+        let problems = check_build_args(
+            Some(0),
+            &args(&["-j=4", "-showtimings", "-showtimings"]),
+            &args(&["--rt=yyc"]),
+        );
+        assert_eq!(
+            problems,
+            [
+                "`jobs` must be at least 1",
+                "`igor_args` contains `-j=4`, but adam already passes `-j`. Use `jobs` instead",
+                "`igor_args` passes `-showtimings` more than once",
+                "`asset_compiler_args` contains `--rt=yyc`, but adam already passes `--rt`",
+            ]
+        );
+    }
+
+    #[test]
+    fn later_layers_replace_flags() {
+        let args = |v: &[&str]| v.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+
+        let mut merged = args(&["-showtimings", "--lf=base.log", "-x"]);
+        merge_args(&mut merged, args(&["--lf=profile.log", "-y"]));
+        assert_eq!(
+            merged,
+            args(&["-showtimings", "-x", "--lf=profile.log", "-y"])
+        );
+        assert!(check_build_args(None, &merged, &[]).is_empty());
+
+        // a repeat within one layer is still a mistake
+        merge_args(&mut merged, args(&["-y", "-y"]));
+        assert_eq!(
+            check_build_args(None, &merged, &[]),
+            ["`igor_args` passes `-y` more than once"]
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use sha2::{Digest, Sha256};
+
+use super::RunOptions;
+
+const FINGERPRINT_FILE: &str = "fingerprint.json";
+
+/// A hash of everything which goes into a build: the project's files, the runtime, the
+/// configuration, the target, the VM or YYC, and any extra compiler arguments. If it matches the
+/// fingerprint saved by the last successful build, that build's output can be run as it is.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Fingerprint {
+    pub digest: String,
+
+    /// The hash of each file, keyed by its path within the project. A file is only hashed again
+    /// when its size or modified time changes.
+    files: BTreeMap<Utf8PathBuf, FileStamp>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+struct FileStamp {
+    size: u64,
+    modified: u128,
+    hash: String,
+}
+
+impl Fingerprint {
+    /// Fingerprints the project as it is now. Files which haven't changed since `previous` reuse
+    /// its hashes. Hidden files and folders, like `.git`, and the output folder are skipped.
+    pub fn new(
+        project_directory: &Utf8Path,
+        run_options: &RunOptions,
+        previous: Option<&Fingerprint>,
+    ) -> std::io::Result<Self> {
+        let output_folder = project_directory.join(&run_options.task.output_folder);
+
+        let walker = walkdir::WalkDir::new(project_directory)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                let hidden = entry.depth() > 0
+                    && entry
+                        .file_name()
+                        .to_str()
+                        .is_some_and(|v| v.starts_with('.'));
+
+                hidden == false && entry.path() != output_folder
+            });
+
+        let mut files = BTreeMap::new();
+        for entry in walker {
+            let entry = entry?;
+            if entry.file_type().is_file() == false {
+                continue;
+            }
+            let Some(path) = Utf8Path::from_path(entry.path()) else {
+                continue;
+            };
+            let relative = path
+                .strip_prefix(project_directory)
+                .unwrap_or(path)
+                .to_owned();
+
+            let metadata = entry.metadata()?;
+            let size = metadata.len();
+            let modified = metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|v| v.as_nanos())
+                .unwrap_or_default();
+
+            let unchanged = previous
+                .and_then(|v| v.files.get(&relative))
+                .filter(|v| v.size == size && v.modified == modified);
+            let hash = match unchanged {
+                Some(stamp) => stamp.hash.clone(),
+                None => format!("{:x}", Sha256::digest(std::fs::read(path)?)),
+            };
+
+            files.insert(
+                relative,
+                FileStamp {
+                    size,
+                    modified,
+                    hash,
+                },
+            );
+        }
+
+        let mut hasher = Sha256::new();
+        for setting in [
+            env!("CARGO_PKG_VERSION"),
+            run_options.platform.runtime_location.as_str(),
+            &run_options.task.config,
+            &run_options.task.target.to_string(),
+            if run_options.task.yyc { "yyc" } else { "vm" },
+            &run_options.task.igor_args.join(" "),
+            &run_options.task.asset_compiler_args.join(" "),
+            if run_options.task.deny_warnings {
+                "deny_warnings"
+            } else {
+                ""
+            },
+        ] {
+            hasher.update(setting);
+            hasher.update([0]);
+        }
+        for (path, stamp) in files.iter() {
+            hasher.update(path.as_str());
+            hasher.update([0]);
+            hasher.update(&stamp.hash);
+            hasher.update([0]);
+        }
+
+        Ok(Self {
+            digest: format!("{:x}", hasher.finalize()),
+            files,
+        })
+    }
+
+    /// Reads the fingerprint saved in the cache folder by the last successful build.
+    pub fn load(cache_folder: &Utf8Path) -> Option<Self> {
+        let txt = std::fs::read_to_string(cache_folder.join(FINGERPRINT_FILE)).ok()?;
+
+        serde_json::from_str(&txt).ok()
+    }
+
+    pub fn save(&self, cache_folder: &Utf8Path) -> std::io::Result<()> {
+        std::fs::write(
+            cache_folder.join(FINGERPRINT_FILE),
+            serde_json::to_string(self).unwrap(),
+        )
+    }
+
+    /// Removes the saved fingerprint, since the output it describes is about to be replaced.
+    pub fn remove(cache_folder: &Utf8Path) {
+        // not our business if this fails
+        let _ = std::fs::remove_file(cache_folder.join(FINGERPRINT_FILE));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{PlatformOptions, TaskOptions};
+
+    #[test]
+    fn digest_follows_files_and_settings() {
+        let dir = std::env::temp_dir().join(format!("adam-fingerprint-{}", std::process::id()));
+        let dir = Utf8PathBuf::try_from(dir).unwrap();
+        std::fs::create_dir_all(dir.join("scripts")).unwrap();
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::write(dir.join("Game.yyp"), "{}").unwrap();
+        std::fs::write(dir.join("scripts/player.gml"), "x = 1;").unwrap();
+
+        let mut options = RunOptions {
+            no_compile: None,
+            task: TaskOptions::default(),
+            platform: PlatformOptions {
+                gms2_application_location: Default::default(),
+                runtime_location: "runtimes/runtime-2024.6.0.205".into(),
+                runtime_pinned: false,
+                runtime_feed: Default::default(),
+                visual_studio_path: Default::default(),
+                user_license_folder: Default::default(),
+                compiler_cache: Default::default(),
+            },
+        };
+
+        let first = Fingerprint::new(&dir, &options, None).unwrap();
+        assert_eq!(first.files.len(), 2);
+
+        // the output folder is not part of the project
+        std::fs::write(dir.join("target/Game.win"), "output").unwrap();
+        let second = Fingerprint::new(&dir, &options, Some(&first)).unwrap();
+        assert_eq!(first, second);
+
+        std::fs::write(dir.join("scripts/player.gml"), "x = 22;").unwrap();
+        let edited = Fingerprint::new(&dir, &options, Some(&second)).unwrap();
+        assert_ne!(edited.digest, second.digest);
+
+        options.task.yyc = true;
+        let yyc = Fingerprint::new(&dir, &options, Some(&edited)).unwrap();
+        assert_ne!(yyc.digest, edited.digest);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::RuntimeVersion;
+
+const BUILT_IN_MARKERS: &str = include_str!("compiler_markers.toml");
+
+/// Markers for some of the stages of a compile. Any marker which isn't set is left as it was.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MarkerSet {
+    /// Lines which show that the asset compile has finished, and the GML compile has started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_start: Option<Vec<String>>,
+
+    /// Lines in the GML compile which report an error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_error: Option<Vec<String>>,
+
+    /// The starts of lines in the GML compile which report a warning. Unlike the other markers,
+    /// these must begin the line, so that an asset like `obj_WarningSign` isn't taken for one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_warning: Option<Vec<String>>,
+
+    /// Lines which show that the GML compile has finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile_end: Option<Vec<String>>,
+
+    /// Lines which show that the build has finished, and the game is being prepared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_end: Option<Vec<String>>,
+
+    /// Whole lines which show that the game has started its main loop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub main_loop: Option<Vec<String>>,
+
+    /// Lines which show that the game has closed, and Igor is done.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_end: Option<Vec<String>>,
+
+    /// Lines the runtime prints while the game starts up, which aren't worth showing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_noise: Option<Vec<String>>,
+}
+
+impl MarkerSet {
+    /// Applies every marker which is set onto the other set.
+    pub fn apply_on(self, target: &mut MarkerSet) {
+        let Self {
+            compile_start,
+            compile_error,
+            compile_warning,
+            compile_end,
+            chunk_end,
+            main_loop,
+            run_end,
+            startup_noise,
+        } = self;
+
+        for (value, target) in [
+            (compile_start, &mut target.compile_start),
+            (compile_error, &mut target.compile_error),
+            (compile_warning, &mut target.compile_warning),
+            (compile_end, &mut target.compile_end),
+            (chunk_end, &mut target.chunk_end),
+            (main_loop, &mut target.main_loop),
+            (run_end, &mut target.run_end),
+            (startup_noise, &mut target.startup_noise),
+        ] {
+            if let Some(value) = value {
+                *target = Some(value);
+            }
+        }
+    }
+}
+
+/// The built in markers, by host OS and runtime version.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MarkerTable {
+    default: MarkerSet,
+    #[serde(default)]
+    host: BTreeMap<String, MarkerSet>,
+    #[serde(default)]
+    runtime: BTreeMap<String, MarkerSet>,
+}
+
+impl MarkerTable {
+    /// Applies the host's markers over the default, and then every runtime table up to the
+    /// runtime, oldest first.
+    fn resolve(self, host: &str, runtime: Option<&RuntimeVersion>) -> MarkerSet {
+        let mut markers = self.default;
+        if let Some(host) = self.host.get(host) {
+            host.clone().apply_on(&mut markers);
+        }
+
+        let mut versions: Vec<_> = self
+            .runtime
+            .into_iter()
+            .filter_map(|(version, set)| Some((RuntimeVersion::parse(&version)?, set)))
+            .filter(|(version, _)| runtime.is_some_and(|runtime| version <= runtime))
+            .collect();
+        versions.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, set) in versions {
+            set.apply_on(&mut markers);
+        }
+
+        markers
+    }
+}
+
+/// The lines adam looks for in Igor's output to follow a compile from one stage to the next.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CompilerMarkers {
+    pub compile_start: Vec<String>,
+    pub compile_error: Vec<String>,
+    pub compile_warning: Vec<String>,
+    pub compile_end: Vec<String>,
+    pub chunk_end: Vec<String>,
+    pub main_loop: Vec<String>,
+    pub run_end: Vec<String>,
+    pub startup_noise: Vec<String>,
+}
+
+impl CompilerMarkers {
+    /// Picks the built in markers for the runtime, and then applies the manifest's overrides.
+    pub fn new(runtime: Option<&RuntimeVersion>, overrides: &MarkerSet) -> Self {
+        let table: MarkerTable =
+            toml::from_str(BUILT_IN_MARKERS).expect("the built in compiler markers are invalid");
+
+        let mut markers = table.resolve(std::env::consts::OS, runtime);
+        overrides.clone().apply_on(&mut markers);
+
+        Self {
+            compile_start: markers.compile_start.unwrap_or_default(),
+            compile_error: markers.compile_error.unwrap_or_default(),
+            compile_warning: markers.compile_warning.unwrap_or_default(),
+            compile_end: markers.compile_end.unwrap_or_default(),
+            chunk_end: markers.chunk_end.unwrap_or_default(),
+            main_loop: markers.main_loop.unwrap_or_default(),
+            run_end: markers.run_end.unwrap_or_default(),
+            startup_noise: markers.startup_noise.unwrap_or_default(),
+        }
+    }
+
+    /// Returns true if the line contains any of the markers.
+    pub fn contains(markers: &[String], line: &str) -> bool {
+        markers.iter().any(|v| line.contains(v.as_str()))
+    }
+
+    /// Returns true if the line, without its leading whitespace, starts with any of the markers.
+    pub fn starts_with(markers: &[String], line: &str) -> bool {
+        let line = line.trim_start();
+
+        markers.iter().any(|v| line.starts_with(v.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_and_manifest_markers_apply_over_the_default() {
+        let markers = CompilerMarkers::new(None, &MarkerSet::default());
+        assert_eq!(markers.compile_start, ["[Compile]"]);
+        assert!(markers.startup_noise.contains(&"Run_Start".to_owned()));
+        if cfg!(target_os = "windows") {
+            assert_eq!(markers.chunk_end, ["Igor complete"]);
+        } else {
+            assert_eq!(markers.chunk_end, ["Finished PrepareGame()"]);
+        }
+
+        let table: MarkerTable = toml::from_str(BUILT_IN_MARKERS).unwrap();
+        for version in table.runtime.keys() {
+            assert!(RuntimeVersion::parse(version).is_some(), "{}", version);
+        }
+
+        let overrides = MarkerSet {
+            compile_end: Some(vec!["GML done".to_owned()]),
+            ..Default::default()
+        };
+        let version = RuntimeVersion::parse("2024.6.0.205").unwrap();
+        let markers = CompilerMarkers::new(Some(&version), &overrides);
+        assert_eq!(markers.compile_end, ["GML done"]);
+        assert_eq!(markers.compile_error, ["Error"]);
+        assert_eq!(markers.compile_warning, ["Warning :", "Warning:"]);
+
+        let warning = "Warning : gml_Script_scr_move(3) : GM2016: unused variable";
+        assert!(CompilerMarkers::starts_with(
+            &markers.compile_warning,
+            warning
+        ));
+        let asset = "[Compile] gml_Object_obj_WarningSign_Create_0";
+        assert!(CompilerMarkers::starts_with(&markers.compile_warning, asset) == false);
+    }
+
+    #[test]
+    fn runtime_tables_apply_from_their_version_on() {
+        let table = r#"
+            [default]
+            compile_start = ["[Compile]"]
+            compile_end = ["Final Compile...finished"]
+
+            [runtime."2024.8"]
+            compile_end = ["GML compile finished"]
+
+            [runtime."2024.11"]
+            compile_start = ["[GML]"]
+        "#;
+        let resolve = |runtime: Option<&str>| {
+            let table: MarkerTable = toml::from_str(table).unwrap();
+            let runtime = runtime.map(|v| RuntimeVersion::parse(v).unwrap());
+            table.resolve("linux", runtime.as_ref())
+        };
+
+        let markers = resolve(Some("2024.8.1.171"));
+        assert_eq!(markers.compile_end.unwrap(), ["GML compile finished"]);
+        assert_eq!(markers.compile_start.unwrap(), ["[Compile]"]);
+
+        let markers = resolve(Some("2024.13.0.190"));
+        assert_eq!(markers.compile_end.unwrap(), ["GML compile finished"]);
+        assert_eq!(markers.compile_start.unwrap(), ["[GML]"]);
+
+        for runtime in [Some("2024.6.0.205"), None] {
+            let markers = resolve(runtime);
+            assert_eq!(markers.compile_end.unwrap(), ["Final Compile...finished"]);
+            assert_eq!(markers.compile_start.unwrap(), ["[Compile]"]);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+
+use super::diagnostics::{CompileError, Diagnostic, Level};
+
+/// How adam reports a build.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageFormat {
+    /// Colored text, for people.
+    #[default]
+    Human,
+    /// One JSON object per line, for tools. See `docs/MESSAGE_FORMAT.md`.
+    Json,
+    /// Like `human`, but compile errors are written on one line each, as
+    /// `path:line:column: error[code]: message`, for editors' problem matchers.
+    Short,
+}
+
+impl MessageFormat {
+    pub fn is_json(self) -> bool {
+        self == MessageFormat::Json
+    }
+
+    pub fn is_short(self) -> bool {
+        self == MessageFormat::Short
+    }
+}
+
+/// The stages of a build, in the order they happen.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+    /// Igor is starting up and compiling assets.
+    Initialize,
+    /// The GML is being compiled.
+    Compile,
+    /// The compiled game is being written out.
+    ChunkBuilder,
+    /// The game is starting.
+    PreRun,
+    /// The game has reached its main loop.
+    Run,
+}
+
+/// Where a game's log line points in the project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    /// The file, relative to the project directory.
+    pub path: Utf8PathBuf,
+    pub line: usize,
+}
+
+/// An event for `--message-format=json`. Each is written on its own line, with a `reason` field
+/// naming the event. The fields are documented in `docs/MESSAGE_FORMAT.md`, and are only ever
+/// added to, so tools should ignore fields and reasons they don't know.
+#[derive(Debug, Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+pub enum Message<'a> {
+    BuildStarted {
+        project: &'a str,
+        kind: &'a str,
+        target: String,
+        config: &'a str,
+        yyc: bool,
+        runtime: &'a Utf8Path,
+    },
+    Stage {
+        stage: Stage,
+    },
+    Diagnostic {
+        level: &'a str,
+        /// The line as the compiler printed it.
+        text: &'a str,
+        #[serde(flatten)]
+        diagnostic: Option<&'a Diagnostic>,
+    },
+    Log {
+        level: Option<&'a str>,
+        message: &'a str,
+        location: Option<Location>,
+    },
+    TestResult {
+        passed: bool,
+    },
+    BuildFinished {
+        success: bool,
+        duration_ms: u128,
+    },
+}
+
+impl Message<'_> {
+    /// Writes the message out as a line of JSON.
+    pub fn emit(&self) {
+        println!("{}", serde_json::to_string(self).unwrap());
+    }
+
+    pub fn diagnostic(error: &CompileError, level: Level) -> Message<'_> {
+        match error {
+            CompileError::Diagnostic(diagnostic) => Message::Diagnostic {
+                level: level.as_str(),
+                text: &diagnostic.text,
+                diagnostic: Some(diagnostic),
+            },
+            CompileError::Raw(text) => Message::Diagnostic {
+                level: level.as_str(),
+                text,
+                diagnostic: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_tagged_by_reason() {
+        let json = |message: Message<'_>| serde_json::to_value(&message).unwrap();
+
+        assert_eq!(
+            json(Message::Stage {
+                stage: Stage::ChunkBuilder
+            }),
+            serde_json::json!({"reason": "stage", "stage": "chunk-builder"})
+        );
+        assert_eq!(
+            json(Message::diagnostic(
+                &CompileError::Raw("Warning: odd".to_owned()),
+                Level::Warning
+            )),
+            serde_json::json!({"reason": "diagnostic", "level": "warning", "text": "Warning: odd"})
+        );
+        assert_eq!(
+            json(Message::BuildFinished {
+                success: true,
+                duration_ms: 1200
+            }),
+            serde_json::json!({"reason": "build-finished", "success": true, "duration_ms": 1200})
+        );
+    }
+}
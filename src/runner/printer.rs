@@ -1,13 +1,22 @@
 use aho_corasick::AhoCorasickBuilder;
 use camino::Utf8Path;
 use gml_log_parser::ScriptMappings;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use std::collections::HashMap;
 
+use super::messages::{Location, Message, MessageFormat};
+
+/// The same logs that `gml_log_parser` looks for, like `gml_Object_obj_player_Step_0:12`.
+static LOG_LOCATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"gml_(?:Object|Script).+?(?::\d+|\(line \d+\))").unwrap());
+
 #[derive(Debug)]
 pub struct Printer {
     str_to_style: std::collections::HashMap<String, console::Style>,
     script_mappings: ScriptMappings,
     aho_corasick: aho_corasick::AhoCorasick,
+    message_format: MessageFormat,
 }
 
 impl Printer {
@@ -20,7 +29,7 @@ impl Printer {
         "Unsetting previous scheduler resolution",
     ];
 
-    pub fn new(scripts_directory: &Utf8Path) -> Self {
+    pub fn new(scripts_directory: &Utf8Path, message_format: MessageFormat) -> Self {
         let str_to_style = HashMap::from([
             ("error".to_string(), console::Style::new().red().bright()),
             ("warn".to_string(), console::Style::new().yellow().bright()),
@@ -36,6 +45,7 @@ impl Printer {
             str_to_style,
             script_mappings: ScriptMappings::from_path(scripts_directory),
             aho_corasick,
+            message_format,
         }
     }
 
@@ -44,6 +54,21 @@ impl Printer {
             return;
         }
 
+        if self.message_format.is_json() {
+            let level = self
+                .aho_corasick
+                .find(&msg)
+                .map(|v| msg[v.start()..v.end()].to_ascii_lowercase());
+
+            Message::Log {
+                level: level.as_deref(),
+                message: &msg,
+                location: self.location(&msg),
+            }
+            .emit();
+            return;
+        }
+
         let mut output = String::new();
         self.aho_corasick
             .replace_all_with(&msg, &mut output, |_, txt, buff| {
@@ -65,4 +90,20 @@ impl Printer {
 
         println!("{}", output);
     }
+
+    /// Finds the file and line which a log points at, if it has one.
+    fn location(&self, msg: &str) -> Option<Location> {
+        let log = LOG_LOCATION.find(msg)?;
+        let parsed = gml_log_parser::parse_log(log.as_str(), &self.script_mappings).ok()?;
+
+        // the log parser gives us `path:line:column`
+        let mut parts = parsed.rsplitn(3, ':');
+        let _column = parts.next()?;
+        let line = parts.next()?.parse().ok()?;
+
+        Some(Location {
+            path: parts.next()?.into(),
+            line,
+        })
+    }
 }
@@ -1,10 +1,21 @@
-use camino::Utf8Path;
+use camino::{Utf8Path, Utf8PathBuf};
+use indicatif::ProgressBar;
 
 use super::{
-    compiler_handler::CompilerHandler, compiler_handler::CompilerOutput, invoke_igor,
+    compiler_handler::CompilerHandler,
+    compiler_handler::CompilerOutput,
+    diagnostics::{self, CompileError, Level},
+    invoke_igor,
     printer::Printer,
+    sarif,
+    timings::{BuildTiming, StageClock},
+    Message, MessageFormat, Stage, StageTimes, TimingHistory,
 };
-use crate::{gm_artifacts::GmMacros, input::RunKind, runner::cache::Cache, RunOptions};
+use crate::{
+    gm_artifacts::GmMacros, input::RunKind, runner::cache::Cache, runner::CompilerMarkers,
+    runtime::RuntimeVersion, RunOptions,
+};
+use gml_log_parser::ScriptMappings;
 use std::{
     io::Lines,
     io::{BufRead, BufReader},
@@ -16,59 +27,162 @@ pub fn run_command(
     run_options: RunOptions,
     run_kind: &RunKind,
 ) -> bool {
-    // and now let's set our kill cmd
-    if run_options.task.close_on_sig_kill {
-        ctrlc::set_handler(move || {
-            use sysinfo::{ProcessExt, SystemExt};
-
-            let mut system = sysinfo::System::new_all();
-            system.refresh_processes();
-
-            let needle = if cfg!(target_os = "windows") {
-                "Runner.exe"
-            } else {
-                "Mac_Runner"
-            };
-
-            for process in system.processes_by_name(needle) {
-                process.kill();
-            }
-        })
-        .unwrap();
-    }
-
     // read the cache if it doesn't exist...
     let final_output = {
         let sub_str = if run_options.task.yyc { "yyc" } else { "vm" };
-        format!("./{}/{}", run_options.task.output_folder, sub_str)
+        format!(
+            "./{}/{}/{}",
+            run_options.task.output_folder, run_options.task.target, sub_str
+        )
     };
 
+    let markers = CompilerMarkers::new(
+        RuntimeVersion::from_runtime_location(&run_options.platform.runtime_location).as_ref(),
+        &run_options.task.compiler_markers,
+    );
+
+    let cache_folder = Utf8PathBuf::from(format!("{}/cache", final_output));
+    let mut history = TimingHistory::load(&cache_folder);
+
     let time = std::time::Instant::now();
     let mut child = invoke_igor(run_kind, &macros, build_bff, &run_options);
 
+    // and now let's set our kill cmd
+    if run_options.task.close_on_sig_kill {
+        kill_on_ctrlc(child.id());
+    }
+
     if run_options.task.verbosity > 0
         || *run_kind == RunKind::Release
         || *run_kind == RunKind::Build
     {
+        let message_format = run_options.task.message_format;
+        let scripts = ScriptMappings::from_path(macros.project_dir.join("scripts"));
+        let mut errors: Vec<CompileError> = vec![];
+        let mut warnings: Vec<CompileError> = vec![];
+        let mut denied = false;
+
+        // we don't draw a progress bar here, but we still time the stages
+        let runs_game = matches!(run_kind, RunKind::Run | RunKind::Test(_));
+        let mut clock = StageClock::new(None, ProgressBar::hidden(), message_format);
+        clock.enter(Stage::Initialize);
+
         let reader = BufReader::new(child.stdout.as_mut().unwrap()).lines();
         for line in reader.map_while(Result::ok) {
-            println!("{}", line.trim());
+            let next = match clock.stage() {
+                Some(Stage::Initialize)
+                    if CompilerMarkers::contains(&markers.compile_start, &line) =>
+                {
+                    Some(Stage::Compile)
+                }
+                Some(Stage::Compile) if CompilerMarkers::contains(&markers.compile_end, &line) => {
+                    Some(Stage::ChunkBuilder)
+                }
+                Some(Stage::ChunkBuilder)
+                    if CompilerMarkers::contains(&markers.chunk_end, &line) =>
+                {
+                    runs_game.then_some(Stage::PreRun)
+                }
+                Some(Stage::PreRun) if markers.main_loop.contains(&line) => Some(Stage::Run),
+                _ => clock.stage(),
+            };
+            match next {
+                Some(stage) if clock.stage() != Some(stage) => clock.enter(stage),
+                Some(_) => {}
+                None => clock.end(),
+            }
+
+            // we don't follow the stages here, but we can still pick out the errors and warnings
+            let level = if CompilerMarkers::contains(&markers.compile_error, &line) {
+                Some(Level::Error)
+            } else if CompilerMarkers::starts_with(&markers.compile_warning, &line) {
+                Some(Level::Warning)
+            } else {
+                None
+            };
+            let diagnostic = level
+                .map(|level| {
+                    let error = CompileError::parse(&line, &macros.project_dir, &scripts);
+                    (level, error)
+                })
+                .filter(|(_, v)| matches!(v, CompileError::Diagnostic(_)));
+
+            match (message_format, &diagnostic) {
+                (MessageFormat::Json, Some((level, error))) => {
+                    Message::diagnostic(error, *level).emit()
+                }
+                (MessageFormat::Json, None) => Message::Log {
+                    level: None,
+                    message: line.trim(),
+                    location: None,
+                }
+                .emit(),
+                (MessageFormat::Short, Some((level, CompileError::Diagnostic(diagnostic)))) => {
+                    match diagnostic.problem_line(level.as_str()) {
+                        Some(line) => println!("{}", line),
+                        None => println!("{}", line.trim()),
+                    }
+                }
+                _ => println!("{}", line.trim()),
+            }
+
+            if let Some((level, error)) = diagnostic {
+                let list = match level {
+                    Level::Error => &mut errors,
+                    Level::Warning => &mut warnings,
+                };
+                if list.contains(&error) == false {
+                    list.push(error);
+                }
+            }
+
+            // there's no point building the rest, or running the game
+            if run_options.task.deny_warnings
+                && warnings.is_empty() == false
+                && CompilerMarkers::contains(&markers.compile_end, &line)
+            {
+                denied = true;
+                break;
+            }
         }
+        write_diagnostics(&run_options, &macros.project_dir, &errors, &warnings);
 
-        match child.wait() {
+        if denied {
+            let _ = child.kill();
+        }
+        let success = match child.wait() {
             Ok(e) => e.success(),
             Err(_) => false,
+        };
+        diagnostics::print_warning_summary(
+            warnings.len(),
+            &macros.project_name,
+            message_format,
+            denied,
+        );
+
+        let success = success && denied == false;
+        if success {
+            // a build ends with igor, but a run's last stage ends at the main loop
+            if runs_game == false {
+                clock.end();
+            }
+            record_timings(&mut history, &cache_folder, &run_options, clock.times());
         }
+
+        success
     } else {
-        let compiler_handler = if run_options.no_compile.is_some() {
-            CompilerHandler::new_re_run()
+        let mut compiler_handler = if run_options.no_compile.is_some() {
+            CompilerHandler::new_re_run(markers.clone())
         } else {
-            CompilerHandler::new_run()
-        };
+            CompilerHandler::new_run(markers.clone())
+        }
+        .with_estimate(history.estimate(&run_options.task.config, run_options.task.yyc));
         // startup the printer in a separate thread...
         let project_dir = macros.project_dir.clone();
+        let message_format = run_options.task.message_format;
         let printer_handler =
-            std::thread::spawn(move || Printer::new(&project_dir.join("scripts")));
+            std::thread::spawn(move || Printer::new(&project_dir.join("scripts"), message_format));
 
         let cache_path = format!("{}/cache.toml", final_output);
         let mut cache: Cache = std::fs::read_to_string(&cache_path)
@@ -85,7 +199,15 @@ pub fn run_command(
             &cache,
         );
 
-        if !matches!(output, CompilerOutput::Errors(_)) {
+        let warnings = diagnostics::parse_errors(compiler_handler.warnings(), &macros.project_dir);
+        if let Some(times) = compiler_handler.stage_times() {
+            record_timings(&mut history, &cache_folder, &run_options, times);
+        }
+
+        if !matches!(
+            output,
+            CompilerOutput::Errors(_) | CompilerOutput::DeniedWarnings
+        ) {
             cache.time = time.elapsed();
             let cache = toml::to_string_pretty(&cache).unwrap();
 
@@ -95,11 +217,42 @@ pub fn run_command(
 
         let mut printer = printer_handler.join().unwrap();
 
+        match &output {
+            CompilerOutput::Errors(errors) => {
+                write_diagnostics(&run_options, &macros.project_dir, errors, &warnings)
+            }
+            // a re-run doesn't compile, so the last errors still stand
+            _ if run_options.no_compile.is_some() => {}
+            _ => write_diagnostics(&run_options, &macros.project_dir, &[], &warnings),
+        }
+
+        if warnings.is_empty() == false {
+            diagnostics::print_warnings(
+                &warnings,
+                &macros.project_name,
+                &macros.project_dir,
+                &mut printer,
+                run_options.task.message_format,
+                matches!(output, CompilerOutput::DeniedWarnings),
+            );
+        }
+
         match output {
-            CompilerOutput::Errors(e) => {
-                for error in e {
-                    printer.print_line(error);
-                }
+            CompilerOutput::Errors(errors) => {
+                diagnostics::print_errors(
+                    &errors,
+                    &macros.project_name,
+                    &macros.project_dir,
+                    &mut printer,
+                    run_options.task.message_format,
+                );
+
+                false
+            }
+            CompilerOutput::DeniedWarnings => {
+                // igor would go on to run the game, so we stop it here
+                let _ = child.kill();
+                let _ = child.wait();
 
                 false
             }
@@ -117,10 +270,160 @@ pub fn run_command(
                     printer.print_line(msg);
                 }
 
-                run_game(&mut reader, &mut printer, run_kind, &run_options)
+                run_game(&mut reader, &mut printer, run_kind, &run_options, &markers)
                     || run_options.no_compile.is_some()
             }
+            CompilerOutput::StartupFailed(lines) => {
+                for line in lines {
+                    printer.print_line(line);
+                }
+
+                false
+            }
+            CompilerOutput::Unrecognized(lines) => {
+                for line in lines {
+                    printer.print_line(line);
+                }
+
+                match child.wait() {
+                    Ok(e) => e.success(),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
+
+/// Runs the output of an earlier build directly with the runner, without invoking Igor. This is
+/// used when nothing has changed since that build, and only for the Windows VM.
+pub fn run_cached(
+    data_win: &Utf8Path,
+    macros: &GmMacros,
+    run_options: &RunOptions,
+    run_kind: &RunKind,
+) -> bool {
+    let mut runner = std::process::Command::new(&macros.x64_runner_path);
+    runner
+        .arg("-game")
+        .arg(data_win)
+        .stdout(std::process::Stdio::piped());
+
+    if run_options.task.verbosity > 0 && run_options.task.message_format.is_json() == false {
+        println!("{:?}", runner);
+    }
+
+    let mut child = match runner.spawn() {
+        Ok(v) => v,
+        Err(e) => {
+            adam_print!(
+                "{}: could not start the runner at `{}` because {}",
+                console::style("adam error").bright().red(),
+                macros.x64_runner_path,
+                e
+            );
+            return false;
         }
+    };
+
+    let mut printer = Printer::new(
+        &macros.project_dir.join("scripts"),
+        run_options.task.message_format,
+    );
+    let mut reader = BufReader::new(child.stdout.take().unwrap()).lines();
+    // there's no igor here, so we only look for a test's needle
+    let markers = CompilerMarkers {
+        run_end: vec![],
+        ..CompilerMarkers::new(None, &run_options.task.compiler_markers)
+    };
+    let found = run_game(&mut reader, &mut printer, run_kind, run_options, &markers);
+
+    if run_kind.is_test() {
+        // the game has told us it passed, so we don't need it anymore
+        if found {
+            let _ = child.kill();
+        }
+        let _ = child.wait();
+
+        found
+    } else {
+        match child.wait() {
+            Ok(e) => e.success(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Kills the process we spawned, and every process under it (which includes the game), on a ctrl-c.
+fn kill_on_ctrlc(pid: u32) {
+    ctrlc::set_handler(move || {
+        use sysinfo::{PidExt, ProcessExt, SystemExt};
+
+        let mut system = sysinfo::System::new();
+        system.refresh_processes();
+
+        let mut tree = vec![sysinfo::Pid::from_u32(pid)];
+        let mut next = 0;
+        while let Some(parent) = tree.get(next).copied() {
+            tree.extend(
+                system
+                    .processes()
+                    .iter()
+                    .filter(|(_, process)| process.parent() == Some(parent))
+                    .map(|(pid, _)| *pid),
+            );
+            next += 1;
+        }
+
+        // children go first, so that none of them is handed to another parent before we reach it
+        for pid in tree.iter().rev() {
+            if let Some(process) = system.process(*pid) {
+                process.kill();
+            }
+        }
+    })
+    .unwrap();
+}
+
+/// Adds the build's stage times to the history in the cache folder.
+fn record_timings(
+    history: &mut TimingHistory,
+    cache_folder: &Utf8Path,
+    run_options: &RunOptions,
+    times: StageTimes,
+) {
+    if times.is_empty() {
+        return;
+    }
+
+    history.record(BuildTiming::new(
+        &run_options.task.config,
+        run_options.task.yyc,
+        times,
+    ));
+
+    // not our business if this fails
+    let _ = history.save(cache_folder);
+}
+
+/// Writes the errors and warnings to `diagnostics_out`, if it's set. Failing to is only worth a
+/// warning.
+fn write_diagnostics(
+    run_options: &RunOptions,
+    project_dir: &Utf8Path,
+    errors: &[CompileError],
+    warnings: &[CompileError],
+) {
+    let Some(path) = &run_options.task.diagnostics_out else {
+        return;
+    };
+
+    if let Err(e) = sarif::write_sarif(path, errors, warnings, project_dir) {
+        adam_print!(
+            "{}: could not write diagnostics to `{}` because {}",
+            console::style("adam warning").bright().yellow(),
+            path,
+            e
+        );
     }
 }
 
@@ -129,17 +432,19 @@ fn run_game(
     printer: &mut Printer,
     run_kind: &RunKind,
     run_options: &RunOptions,
+    markers: &CompilerMarkers,
 ) -> bool {
     let mut found = false;
 
-    let kill_word = if matches!(run_kind, RunKind::Test(_)) {
-        &run_options.task.test_success_needle
+    let needle = [run_options.task.test_success_needle.clone()];
+    let kill_words: &[String] = if matches!(run_kind, RunKind::Test(_)) {
+        &needle
     } else {
-        "Igor complete"
+        &markers.run_end
     };
 
     for line in lines.flatten() {
-        if line.contains(kill_word) {
+        if CompilerMarkers::contains(kill_words, &line) {
             found = true;
             break;
         }
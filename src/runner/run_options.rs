@@ -1,15 +1,16 @@
 use camino::Utf8PathBuf;
+use serde::Serialize;
 
-use crate::AnyResult;
+use crate::{igor::PlatformKind, AnyResult};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct RunOptions {
     pub no_compile: Option<Utf8PathBuf>,
     pub task: TaskOptions,
     pub platform: PlatformOptions,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct TaskOptions {
     /// Uses the YYC instead of the default VM. If this is the case, then we'll need to check
     /// your Visual Studio path on Windows.
@@ -23,6 +24,18 @@ pub struct TaskOptions {
     /// Specifies a configuration. If not passed, we use `Default` for our Config.
     pub config: String,
 
+    /// The platform to build for. Defaults to the host platform.
+    pub target: PlatformKind,
+
+    /// Specifies the target Yyp to build, if there are multiple.
+    pub yyp: Option<String>,
+
+    /// If true, we build every member of the workspace instead of the current directory.
+    pub workspace: bool,
+
+    /// The project directories listed in the manifest's `[workspace]` table.
+    pub workspace_members: Vec<Utf8PathBuf>,
+
     /// Verbosity level. Can use multiple times, like '-vv'. >0 disables pretty compiles, >1 enables igor verbosity, >2 enables gmac verbosity
     pub verbosity: u8,
 
@@ -43,9 +56,36 @@ pub struct TaskOptions {
 
     /// When set, we won't do `check` before anything.
     pub no_build_script: bool,
+
+    /// The number of jobs Igor and GMAssetCompiler compile with.
+    pub jobs: u32,
+
+    /// Extra arguments passed to Igor, before the platform and action.
+    pub igor_args: Vec<String>,
+
+    /// Extra arguments passed to GMAssetCompiler, when we build with it directly.
+    pub asset_compiler_args: Vec<String>,
+
+    /// When set, any warning in the GML compile fails the build.
+    pub deny_warnings: bool,
+
+    /// Overrides for the lines we look for in Igor's output to follow the compile.
+    pub compiler_markers: super::MarkerSet,
+
+    /// Whether we report the build as text or as JSON.
+    pub message_format: super::MessageFormat,
+
+    /// When set, we write the compile's errors to this file as SARIF.
+    pub diagnostics_out: Option<Utf8PathBuf>,
+
+    /// When set, we write the build files and print the command we would run, but don't run it.
+    pub dry_run: bool,
+
+    /// When set, we print the command and environment we hand to Igor before running it.
+    pub print_command: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
 pub struct PlatformOptions {
     /// The path to your Gms2 installation. Defaults to C drive on Windows and Applications on macOS. If you use Steam, you will need to pass in that fullpath to the .exe, or the .app on macOS.
     pub gms2_application_location: Utf8PathBuf,
@@ -53,6 +93,12 @@ pub struct PlatformOptions {
     /// This sets a complete path to the runtime location.
     pub runtime_location: Utf8PathBuf,
 
+    /// If true, the runtime was chosen by the user, so we won't go looking for a newer one.
+    pub runtime_pinned: bool,
+
+    /// The url or path of the rss feed which `adam runtime install` installs runtimes from.
+    pub runtime_feed: String,
+
     /// Use this visual studio path, instead of the visual studio path within the `user_folder`
     /// at `~/.config`. This is only relevant on Windows.
     ///
@@ -124,6 +170,10 @@ impl Default for TaskOptions {
             yyc: false,
             no_user_folder: false,
             config: "Default".to_string(),
+            target: crate::gm_artifacts::PLATFORM_KIND,
+            yyp: None,
+            workspace: false,
+            workspace_members: vec![],
             verbosity: 0,
             output_folder: "target".into(),
             ignore_cache: 0,
@@ -131,6 +181,15 @@ impl Default for TaskOptions {
             test_success_needle: "RUN_SUCCESS".to_string(),
             close_on_sig_kill: false,
             no_build_script: false,
+            jobs: 8,
+            igor_args: vec![],
+            asset_compiler_args: vec![],
+            deny_warnings: false,
+            compiler_markers: Default::default(),
+            message_format: Default::default(),
+            diagnostics_out: None,
+            dry_run: false,
+            print_command: false,
         }
     }
 }
@@ -0,0 +1,162 @@
+use camino::Utf8Path;
+use serde_json::json;
+
+use super::diagnostics::{CompileError, Level};
+
+/// Writes the compile's errors and warnings to `path` as a SARIF 2.1 log, with file paths relative
+/// to the project directory. A build without either writes a log without results, so that an old
+/// log never outlives what it reported.
+pub fn write_sarif(
+    path: &Utf8Path,
+    errors: &[CompileError],
+    warnings: &[CompileError],
+    project_directory: &Utf8Path,
+) -> std::io::Result<()> {
+    let log = sarif_log(errors, warnings, project_directory);
+
+    if let Some(parent) = path.parent().filter(|v| v.as_str().is_empty() == false) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&log).unwrap())
+}
+
+fn sarif_log(
+    errors: &[CompileError],
+    warnings: &[CompileError],
+    project_directory: &Utf8Path,
+) -> serde_json::Value {
+    let errors = errors.iter().map(|v| (Level::Error, v));
+    let warnings = warnings.iter().map(|v| (Level::Warning, v));
+
+    let results: Vec<_> = errors
+        .chain(warnings)
+        .map(|(level, error)| match error {
+            CompileError::Diagnostic(diagnostic) => {
+                let mut result = json!({
+                    "ruleId": diagnostic.code.clone().unwrap_or_else(|| rule(level)),
+                    "level": level.as_str(),
+                    "message": { "text": diagnostic.message },
+                });
+
+                if let Some(path) = &diagnostic.path {
+                    let mut region = json!({ "startLine": diagnostic.line });
+                    if let Some(column) = diagnostic.column {
+                        region["startColumn"] = column.into();
+                    }
+
+                    result["locations"] = json!([{
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": path.as_str().replace('\\', "/"),
+                                "uriBaseId": "PROJECT_DIR",
+                            },
+                            "region": region,
+                        }
+                    }]);
+                } else {
+                    result["locations"] = json!([{
+                        "logicalLocations": [{ "name": diagnostic.asset }]
+                    }]);
+                }
+
+                result
+            }
+            CompileError::Raw(line) => json!({
+                "ruleId": rule(level),
+                "level": level.as_str(),
+                "message": { "text": line.trim() },
+            }),
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "adam",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                }
+            },
+            "originalUriBaseIds": {
+                "PROJECT_DIR": { "uri": directory_uri(project_directory) }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// The rule we report a diagnostic under when the compiler didn't give a code.
+fn rule(level: Level) -> String {
+    format!("gml-compile-{}", level.as_str())
+}
+
+/// The `file://` uri of a directory, ending in a `/` as SARIF expects.
+fn directory_uri(directory: &Utf8Path) -> String {
+    let path = directory.as_str().replace('\\', "/").replace(' ', "%20");
+    let path = path.trim_end_matches('/');
+
+    if path.starts_with('/') {
+        format!("file://{}/", path)
+    } else {
+        format!("file:///{}/", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::diagnostics::Diagnostic;
+
+    #[test]
+    fn errors_are_sarif_results() {
+        let errors = [
+            CompileError::Diagnostic(Diagnostic {
+                asset: "obj_player".to_owned(),
+                event: Some("Step_0".to_owned()),
+                path: Some("objects/obj_player/Step_0.gml".into()),
+                line: 4,
+                column: Some(9),
+                message: "malformed assignment".to_owned(),
+                code: Some("GM1009".to_owned()),
+                text: String::new(),
+            }),
+            CompileError::Raw("Error: something else".to_owned()),
+        ];
+        let warnings = [CompileError::Raw("Warning: odd".to_owned())];
+
+        let log = sarif_log(&errors, &warnings, Utf8Path::new("/home/me/my game"));
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["originalUriBaseIds"]["PROJECT_DIR"]["uri"],
+            "file:///home/me/my%20game/"
+        );
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "ruleId": "GM1009",
+                "level": "error",
+                "message": { "text": "malformed assignment" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": "objects/obj_player/Step_0.gml",
+                            "uriBaseId": "PROJECT_DIR",
+                        },
+                        "region": { "startLine": 4, "startColumn": 9 },
+                    }
+                }],
+            })
+        );
+        assert_eq!(run["results"][1]["ruleId"], "gml-compile-error");
+        assert_eq!(run["results"][2]["ruleId"], "gml-compile-warning");
+        assert_eq!(run["results"][2]["level"], "warning");
+
+        assert_eq!(
+            directory_uri(Utf8Path::new("C:\\games\\my_game\\")),
+            "file:///C:/games/my_game/"
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use std::time::{Duration, Instant, SystemTime};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+
+use super::{Message, MessageFormat, Stage};
+
+/// The file in the cache folder which holds the history.
+const HISTORY_FILE: &str = "timings.toml";
+
+/// How many builds we keep for each configuration and VM/YYC.
+const KEPT_BUILDS: usize = 50;
+
+/// How many of the latest builds the estimate for the next one averages.
+const ROLLING_BUILDS: usize = 5;
+
+/// The stages we time. The game's own run isn't one of them, since it lasts as long as you play.
+pub const TIMED_STAGES: [Stage; 4] = [
+    Stage::Initialize,
+    Stage::Compile,
+    Stage::ChunkBuilder,
+    Stage::PreRun,
+];
+
+/// How long each stage of a build took, in milliseconds. A stage which the build didn't go
+/// through, such as the compile in a `--no-compile` run, is left out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageTimes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initialize: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compile: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk_builder: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_run: Option<u64>,
+}
+
+impl StageTimes {
+    fn slot(&mut self, stage: Stage) -> Option<&mut Option<u64>> {
+        match stage {
+            Stage::Initialize => Some(&mut self.initialize),
+            Stage::Compile => Some(&mut self.compile),
+            Stage::ChunkBuilder => Some(&mut self.chunk_builder),
+            Stage::PreRun => Some(&mut self.pre_run),
+            Stage::Run => None,
+        }
+    }
+
+    pub fn get(&self, stage: Stage) -> Option<Duration> {
+        let ms = match stage {
+            Stage::Initialize => self.initialize,
+            Stage::Compile => self.compile,
+            Stage::ChunkBuilder => self.chunk_builder,
+            Stage::PreRun => self.pre_run,
+            Stage::Run => None,
+        };
+
+        ms.map(Duration::from_millis)
+    }
+
+    pub fn set(&mut self, stage: Stage, duration: Duration) {
+        if let Some(slot) = self.slot(stage) {
+            *slot = Some(duration.as_millis() as u64);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        TIMED_STAGES.iter().all(|v| self.get(*v).is_none())
+    }
+
+    /// The time of every stage which was timed.
+    pub fn total(&self) -> Duration {
+        TIMED_STAGES.iter().filter_map(|v| self.get(*v)).sum()
+    }
+
+    /// The average of each stage, over the builds which went through it.
+    pub fn average<'a>(times: impl Iterator<Item = &'a StageTimes> + Clone) -> Self {
+        let mut average = StageTimes::default();
+
+        for stage in TIMED_STAGES {
+            let (sum, count) = times
+                .clone()
+                .filter_map(|v| v.get(stage))
+                .fold((Duration::ZERO, 0), |(sum, count), v| (sum + v, count + 1));
+
+            if count > 0 {
+                average.set(stage, sum / count);
+            }
+        }
+
+        average
+    }
+}
+
+/// The stage times of one build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildTiming {
+    /// The Gms2 configuration which was built.
+    pub config: String,
+    /// `vm` or `yyc`.
+    pub kind: String,
+    /// When the build finished, in seconds since the unix epoch.
+    pub finished: u64,
+    pub stages: StageTimes,
+}
+
+impl BuildTiming {
+    pub fn new(config: &str, yyc: bool, stages: StageTimes) -> Self {
+        Self {
+            config: config.to_owned(),
+            kind: if yyc { "yyc" } else { "vm" }.to_owned(),
+            finished: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|v| v.as_secs())
+                .unwrap_or_default(),
+            stages,
+        }
+    }
+}
+
+/// The stage times of the last builds of a target, kept in its cache folder.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TimingHistory {
+    #[serde(default)]
+    pub builds: Vec<BuildTiming>,
+}
+
+impl TimingHistory {
+    pub fn path(cache: &Utf8Path) -> Utf8PathBuf {
+        cache.join(HISTORY_FILE)
+    }
+
+    /// Loads the history in the cache folder. A missing or unreadable history is empty.
+    pub fn load(cache: &Utf8Path) -> Self {
+        std::fs::read_to_string(Self::path(cache))
+            .ok()
+            .and_then(|txt| toml::from_str(&txt).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, cache: &Utf8Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(cache)?;
+        std::fs::write(Self::path(cache), toml::to_string_pretty(self).unwrap())
+    }
+
+    /// Adds a build, dropping the oldest build with the same configuration and VM/YYC once there
+    /// are too many.
+    pub fn record(&mut self, timing: BuildTiming) {
+        let count = self.builds_for(&timing.config, &timing.kind).count();
+        if count >= KEPT_BUILDS {
+            let mut extra = count + 1 - KEPT_BUILDS;
+            self.builds.retain(|v| {
+                let drop = extra > 0 && v.config == timing.config && v.kind == timing.kind;
+                if drop {
+                    extra -= 1;
+                }
+                drop == false
+            });
+        }
+
+        self.builds.push(timing);
+    }
+
+    /// The builds for the configuration and VM/YYC, oldest first.
+    pub fn builds_for<'a>(
+        &'a self,
+        config: &'a str,
+        kind: &'a str,
+    ) -> impl Iterator<Item = &'a BuildTiming> + Clone + 'a {
+        self.builds
+            .iter()
+            .filter(move |v| v.config == config && v.kind == kind)
+    }
+
+    /// How long we expect each stage of the next build to take, from the average of the last few.
+    pub fn estimate(&self, config: &str, yyc: bool) -> Option<StageTimes> {
+        let kind = if yyc { "yyc" } else { "vm" };
+        let builds: Vec<_> = self.builds_for(config, kind).collect();
+        let recent = &builds[builds.len().saturating_sub(ROLLING_BUILDS)..];
+
+        let estimate = StageTimes::average(recent.iter().map(|v| &v.stages));
+        (estimate.is_empty() == false).then_some(estimate)
+    }
+}
+
+/// A stage which took noticeably longer in the last build than it usually does.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub stage: Stage,
+    pub latest: Duration,
+    pub average: Duration,
+}
+
+/// Compares the last build's stages against the average of the builds before it. A stage has
+/// regressed if it took at least a fifth, and at least a second, longer than usual.
+pub fn regressions(builds: &[&BuildTiming]) -> Vec<Regression> {
+    let Some((latest, earlier)) = builds.split_last() else {
+        return vec![];
+    };
+    // a couple of builds isn't enough to know what's usual
+    if earlier.len() < 3 {
+        return vec![];
+    }
+    let earlier = &earlier[earlier.len().saturating_sub(10)..];
+    let average = StageTimes::average(earlier.iter().map(|v| &v.stages));
+
+    TIMED_STAGES
+        .into_iter()
+        .filter_map(|stage| {
+            let latest = latest.stages.get(stage)?;
+            let average = average.get(stage)?;
+
+            (latest > average.mul_f64(1.2) && latest - average >= Duration::from_secs(1)).then_some(
+                Regression {
+                    stage,
+                    latest,
+                    average,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Times the stages of a build as it moves through them, and keeps the progress bar's length up
+/// to date with the estimate for the stages left.
+pub struct StageClock {
+    current: Option<(Stage, Instant)>,
+    times: StageTimes,
+    started: Instant,
+    estimate: Option<StageTimes>,
+    progress_bar: ProgressBar,
+    message_format: MessageFormat,
+}
+
+impl StageClock {
+    pub fn new(
+        estimate: Option<StageTimes>,
+        progress_bar: ProgressBar,
+        message_format: MessageFormat,
+    ) -> Self {
+        Self {
+            current: None,
+            times: StageTimes::default(),
+            started: Instant::now(),
+            estimate,
+            progress_bar,
+            message_format,
+        }
+    }
+
+    /// Finishes the current stage, if there is one, and starts the next.
+    pub fn enter(&mut self, stage: Stage) {
+        self.end();
+        self.current = Some((stage, Instant::now()));
+
+        if self.message_format.is_json() {
+            Message::Stage { stage }.emit();
+        }
+
+        if let Some(estimate) = &self.estimate {
+            // this stage and the ones after it, as long as they usually take
+            let left: Duration = TIMED_STAGES
+                .into_iter()
+                .skip_while(|v| *v != stage)
+                .filter_map(|v| estimate.get(v))
+                .sum();
+            self.progress_bar
+                .set_length((self.started.elapsed() + left).as_secs().max(1));
+        }
+    }
+
+    /// Finishes the current stage without starting another.
+    pub fn end(&mut self) {
+        if let Some((stage, start)) = self.current.take() {
+            self.times.set(stage, start.elapsed());
+        }
+    }
+
+    /// The stage we're in, if we haven't ended.
+    pub fn stage(&self) -> Option<Stage> {
+        self.current.map(|(stage, _)| stage)
+    }
+
+    /// The time of each stage which has finished.
+    pub fn times(&self) -> StageTimes {
+        self.times
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(config: &str, compile: u64) -> BuildTiming {
+        BuildTiming {
+            config: config.to_owned(),
+            kind: "vm".to_owned(),
+            finished: 0,
+            stages: StageTimes {
+                initialize: Some(1000),
+                compile: Some(compile),
+                chunk_builder: None,
+                pre_run: Some(500),
+            },
+        }
+    }
+
+    #[test]
+    fn history_estimates_and_finds_regressions() {
+        let mut history = TimingHistory::default();
+        for compile in [9000, 10000, 11000, 20000] {
+            history.record(build("Default", compile));
+        }
+        history.record(build("Release", 60000));
+
+        let estimate = history.estimate("Default", false).unwrap();
+        assert_eq!(estimate.compile, Some(12500));
+        assert_eq!(estimate.chunk_builder, None);
+        assert_eq!(estimate.total(), Duration::from_millis(14000));
+        assert_eq!(history.estimate("Default", true), None);
+
+        let builds: Vec<_> = history.builds_for("Default", "vm").collect();
+        assert_eq!(
+            regressions(&builds),
+            [Regression {
+                stage: Stage::Compile,
+                latest: Duration::from_secs(20),
+                average: Duration::from_secs(10),
+            }]
+        );
+        assert!(regressions(&builds[..3]).is_empty());
+
+        for _ in 0..KEPT_BUILDS {
+            history.record(build("Default", 1000));
+        }
+        assert_eq!(history.builds_for("Default", "vm").count(), KEPT_BUILDS);
+        assert_eq!(history.builds_for("Release", "vm").count(), 1);
+
+        let toml = toml::to_string_pretty(&history).unwrap();
+        let loaded: TimingHistory = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.builds, history.builds);
+    }
+}
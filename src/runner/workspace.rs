@@ -0,0 +1,81 @@
+use std::process::{Command, ExitCode};
+
+use camino::Utf8Path;
+
+/// Runs the current command in every workspace member, one after another, by invoking adam
+/// again with the same arguments (minus `--workspace`, or `ADAM_WORKSPACE`) inside each member's
+/// directory.
+pub fn run_workspace(members: &[impl AsRef<Utf8Path>]) -> ExitCode {
+    if members.is_empty() {
+        adam_print!(
+            "{}: `--workspace` was passed, but the manifest has no `[workspace]` members",
+            console::style("adam error").bright().red(),
+        );
+
+        return ExitCode::FAILURE;
+    }
+
+    let current_exe = match std::env::current_exe() {
+        Ok(v) => v,
+        Err(e) => {
+            adam_print!(
+                "{}: couldn't find the adam executable because {}",
+                console::style("adam error").bright().red(),
+                e
+            );
+
+            return ExitCode::FAILURE;
+        }
+    };
+    let args: Vec<_> = std::env::args_os()
+        .skip(1)
+        .filter(|arg| arg != "--workspace")
+        .collect();
+
+    let mut results = Vec::with_capacity(members.len());
+    for (i, member) in members.iter().enumerate() {
+        let member = member.as_ref();
+        adam_print!(
+            "{} {} ({}/{})",
+            console::style("Workspace").cyan().bright(),
+            member,
+            i + 1,
+            members.len()
+        );
+
+        let success = if member.is_dir() {
+            Command::new(&current_exe)
+                .args(&args)
+                .env_remove(format!("{}WORKSPACE", crate::input::ENV_PREFIX))
+                .current_dir(member)
+                .status()
+                .is_ok_and(|status| status.success())
+        } else {
+            adam_print!(
+                "{}: workspace member `{}` is not a directory",
+                console::style("adam error").bright().red(),
+                member
+            );
+
+            false
+        };
+
+        results.push((member, success));
+    }
+
+    adam_print!("adam workspace results:");
+    for (member, success) in results.iter() {
+        let style_value = if *success {
+            console::style("ok").green().bright()
+        } else {
+            console::style("FAILED").red().bright()
+        };
+        adam_print!("    {:<6} {}", style_value, member);
+    }
+
+    if results.iter().all(|(_, success)| *success) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
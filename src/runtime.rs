@@ -0,0 +1,149 @@
+mod discovery;
+pub use discovery::*;
+
+mod feed;
+pub use feed::*;
+
+mod install;
+pub use install::*;
+
+use std::process::ExitCode;
+
+use crate::{igor, input::RuntimeOptions, RunOptions};
+
+pub fn runtime_request(request: RuntimeOptions, mut options: RunOptions) -> ExitCode {
+    match request {
+        RuntimeOptions::List(build_options) => {
+            build_options.write_to_options(&mut options);
+
+            let Some(runtimes_directory) = runtimes_directory(&options.platform) else {
+                println!(
+                    "{}: runtime location `{}` has no parent directory",
+                    console::style("adam error").bright().red(),
+                    options.platform.runtime_location
+                );
+                return ExitCode::FAILURE;
+            };
+
+            let runtimes = installed_runtimes(&runtimes_directory);
+            if runtimes.is_empty() {
+                println!(
+                    "{}: no runtimes found in `{}`",
+                    console::style("adam warning").bright().yellow(),
+                    runtimes_directory
+                );
+                return ExitCode::SUCCESS;
+            }
+
+            let selected = resolve_current_project(&mut options);
+            for runtime in runtimes.iter().rev() {
+                if selected.path == runtime.path {
+                    println!(
+                        "{} {}",
+                        console::style("*").green().bright(),
+                        console::style(&runtime.version).bold()
+                    );
+                } else {
+                    println!("  {}", runtime.version);
+                }
+            }
+
+            ExitCode::SUCCESS
+        }
+        RuntimeOptions::Install {
+            version,
+            from,
+            feed,
+            force,
+            build_options,
+        } => {
+            build_options.write_to_options(&mut options);
+
+            let Some(version) = RuntimeVersion::parse(&version) else {
+                println!(
+                    "{}: `{}` is not a runtime version. Versions look like `2024.6.0.205`",
+                    console::style("adam error").bright().red(),
+                    version
+                );
+                return ExitCode::FAILURE;
+            };
+
+            let Some(runtimes_directory) = runtimes_directory(&options.platform) else {
+                println!(
+                    "{}: runtime location `{}` has no parent directory",
+                    console::style("adam error").bright().red(),
+                    options.platform.runtime_location
+                );
+                return ExitCode::FAILURE;
+            };
+
+            let feed = feed.unwrap_or(options.platform.runtime_feed);
+            let source = match &from {
+                Some(archive) => RuntimeSource::Archive {
+                    archive,
+                    feed: &feed,
+                },
+                None => RuntimeSource::Feed(&feed),
+            };
+
+            match install_runtime(&version, source, &runtimes_directory, force) {
+                Ok(path) => {
+                    println!(
+                        "{}: installed runtime {} to `{}`",
+                        console::style("success").green().bright(),
+                        version,
+                        path
+                    );
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!(
+                        "{}: could not install runtime {}: {:#}",
+                        console::style("adam error").bright().red(),
+                        version,
+                        e
+                    );
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        RuntimeOptions::Which(build_options) => {
+            build_options.write_to_options(&mut options);
+
+            let selection = resolve_current_project(&mut options);
+            println!("{}", selection.path);
+            println!(
+                "{} ({})",
+                console::style(&selection.version).bold(),
+                selection.reason
+            );
+
+            if selection.path.exists() {
+                ExitCode::SUCCESS
+            } else {
+                println!(
+                    "{}: this runtime is not installed",
+                    console::style("adam error").bright().red(),
+                );
+
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Resolves the runtime for the project in the current directory, if there is one.
+pub fn resolve_current_project(options: &mut RunOptions) -> RuntimeSelection {
+    let project_path = igor::ApplicationData::new(options.task.yyp.as_deref())
+        .ok()
+        .and_then(|application_data| {
+            application_data.project_name.map(|name| {
+                application_data
+                    .current_directory
+                    .join(name)
+                    .with_extension("yyp")
+            })
+        });
+
+    resolve_runtime(&mut options.platform, project_path.as_deref())
+}
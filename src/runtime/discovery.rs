@@ -0,0 +1,245 @@
+use std::{cmp::Ordering, fmt};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::runner::PlatformOptions;
+
+/// A dotted runtime version, such as `2024.6.0.205`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RuntimeVersion {
+    parts: Vec<u64>,
+}
+
+impl RuntimeVersion {
+    pub fn parse(input: &str) -> Option<Self> {
+        let parts: Vec<u64> = input
+            .split('.')
+            .map(|v| v.parse().ok())
+            .collect::<Option<_>>()?;
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(Self { parts })
+        }
+    }
+
+    /// Reads the version from a runtime's folder, such as `runtime-2024.6.0.205`.
+    pub fn from_runtime_location(runtime_location: &Utf8Path) -> Option<Self> {
+        Self::parse(runtime_location.file_name()?.strip_prefix("runtime-")?)
+    }
+
+    /// Returns `true` if both versions are from the same release, ignoring the build number.
+    /// The IDE and its runtime share a release, but almost never a build number.
+    pub fn same_release(&self, other: &Self) -> bool {
+        let len = self.parts.len().min(other.parts.len()).min(3);
+
+        self.parts[..len] == other.parts[..len]
+    }
+}
+
+impl Ord for RuntimeVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts.cmp(&other.parts)
+    }
+}
+
+impl PartialOrd for RuntimeVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for RuntimeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let txt = self
+            .parts
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+
+        f.pad(&txt)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledRuntime {
+    pub version: RuntimeVersion,
+    pub path: Utf8PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeSelection {
+    /// The version of the runtime, or the folder name if we couldn't parse it.
+    pub version: String,
+    pub path: Utf8PathBuf,
+    pub reason: SelectionReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionReason {
+    /// The user gave us a `runtime` or a `runtime_location_override`.
+    Pinned,
+    /// The newest installed runtime from the same release as the project's IDE version.
+    MatchesProject(RuntimeVersion),
+    /// The newest installed runtime.
+    Newest,
+    /// We didn't find any runtimes, so we're using our compiled in default.
+    Default,
+}
+
+impl fmt::Display for SelectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectionReason::Pinned => write!(f, "set by configuration"),
+            SelectionReason::MatchesProject(v) => write!(f, "matches project version {}", v),
+            SelectionReason::Newest => write!(f, "newest installed"),
+            SelectionReason::Default => write!(f, "adam default"),
+        }
+    }
+}
+
+/// The `Cache/runtimes` folder which our current runtime lives in.
+pub fn runtimes_directory(platform: &PlatformOptions) -> Option<Utf8PathBuf> {
+    platform.runtime_location.parent().map(|v| v.to_owned())
+}
+
+/// Finds every `runtime-*` folder in the given directory, sorted from oldest to newest.
+pub fn installed_runtimes(runtimes_directory: &Utf8Path) -> Vec<InstalledRuntime> {
+    let Ok(dir) = runtimes_directory.read_dir_utf8() else {
+        return vec![];
+    };
+
+    let mut runtimes: Vec<_> = dir
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let version = entry.file_name().strip_prefix("runtime-")?;
+
+            Some(InstalledRuntime {
+                version: RuntimeVersion::parse(version)?,
+                path: entry.path().to_owned(),
+            })
+        })
+        .collect();
+    runtimes.sort_by(|a, b| a.version.cmp(&b.version));
+
+    runtimes
+}
+
+/// Reads the `IDEVersion` out of a yyp's `MetaData`.
+pub fn project_ide_version(yyp_path: &Utf8Path) -> Option<RuntimeVersion> {
+    let yyp = crate::igor::read_yyp(yyp_path)?;
+
+    RuntimeVersion::parse(yyp.get("MetaData")?.get("IDEVersion")?.as_str()?)
+}
+
+/// Picks a runtime from the installed runtimes. We prefer the newest runtime from the same
+/// release as the project, and otherwise, the newest runtime.
+pub fn select_runtime<'a>(
+    installed: &'a [InstalledRuntime],
+    project_version: Option<&RuntimeVersion>,
+) -> Option<(&'a InstalledRuntime, SelectionReason)> {
+    if let Some(project_version) = project_version {
+        let matching = installed
+            .iter()
+            .rev()
+            .find(|v| v.version.same_release(project_version));
+
+        if let Some(matching) = matching {
+            return Some((
+                matching,
+                SelectionReason::MatchesProject(project_version.clone()),
+            ));
+        }
+    }
+
+    installed.last().map(|v| (v, SelectionReason::Newest))
+}
+
+/// Sets the `runtime_location` to a discovered runtime, unless the user has pinned one.
+pub fn resolve_runtime(
+    platform: &mut PlatformOptions,
+    project_yyp: Option<&Utf8Path>,
+) -> RuntimeSelection {
+    let folder_version = |path: &Utf8Path| {
+        let name = path.file_name().unwrap_or_default();
+        name.strip_prefix("runtime-").unwrap_or(name).to_owned()
+    };
+
+    let discovered = if platform.runtime_pinned {
+        None
+    } else {
+        runtimes_directory(platform).and_then(|runtimes_directory| {
+            let installed = installed_runtimes(&runtimes_directory);
+            let project_version = project_yyp.and_then(project_ide_version);
+
+            select_runtime(&installed, project_version.as_ref())
+                .map(|(runtime, reason)| (runtime.clone(), reason))
+        })
+    };
+
+    if let Some((runtime, reason)) = discovered {
+        platform.runtime_location = runtime.path.clone();
+
+        return RuntimeSelection {
+            version: runtime.version.to_string(),
+            path: runtime.path,
+            reason,
+        };
+    }
+
+    RuntimeSelection {
+        version: folder_version(&platform.runtime_location),
+        path: platform.runtime_location.clone(),
+        reason: if platform.runtime_pinned {
+            SelectionReason::Pinned
+        } else {
+            SelectionReason::Default
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(version: &str) -> InstalledRuntime {
+        InstalledRuntime {
+            version: RuntimeVersion::parse(version).unwrap(),
+            path: format!("runtimes/runtime-{}", version).into(),
+        }
+    }
+
+    #[test]
+    fn version_ordering() {
+        let old = RuntimeVersion::parse("2024.6.0.205").unwrap();
+        let new = RuntimeVersion::parse("2024.11.0.226").unwrap();
+
+        assert!(old < new);
+        assert_eq!(new.to_string(), "2024.11.0.226");
+        assert!(RuntimeVersion::parse("runtime-2024").is_none());
+    }
+
+    #[test]
+    fn selection() {
+        let installed = vec![
+            runtime("2024.6.0.205"),
+            runtime("2024.6.0.210"),
+            runtime("2024.11.0.226"),
+        ];
+
+        let project = RuntimeVersion::parse("2024.6.0.163").unwrap();
+        let (selected, reason) = select_runtime(&installed, Some(&project)).unwrap();
+        assert_eq!(selected.version.to_string(), "2024.6.0.210");
+        assert_eq!(reason, SelectionReason::MatchesProject(project));
+
+        let unknown = RuntimeVersion::parse("2023.1.0.1").unwrap();
+        let (selected, reason) = select_runtime(&installed, Some(&unknown)).unwrap();
+        assert_eq!(selected.version.to_string(), "2024.11.0.226");
+        assert_eq!(reason, SelectionReason::Newest);
+
+        assert!(select_runtime(&[], None).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use camino::Utf8Path;
+use color_eyre::{eyre::Context, Help};
+
+use super::RuntimeVersion;
+use crate::AnyResult;
+
+/// A single runtime listed in a runtime feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedEntry {
+    pub version: RuntimeVersion,
+    /// Either a url, or, for a feed on disk, a path relative to the feed.
+    pub location: String,
+    /// The size of the archive in bytes, if the feed gives one.
+    pub length: Option<u64>,
+    pub checksum: Option<Checksum>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    Md5(String),
+    Sha256(String),
+}
+
+/// Returns `true` if the location should be downloaded rather than read from disk.
+pub fn is_remote(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Reads the feed at the given url or path.
+pub fn read_feed(feed: &str) -> AnyResult<Vec<FeedEntry>> {
+    let txt = if is_remote(feed) {
+        ureq::get(feed)
+            .call()
+            .with_note(|| format!("could not download runtime feed `{}`", feed))?
+            .into_string()?
+    } else {
+        std::fs::read_to_string(feed)
+            .with_note(|| format!("could not read runtime feed `{}`", feed))?
+    };
+
+    parse_feed(&txt).wrap_err_with(|| format!("invalid runtime feed `{}`", feed))
+}
+
+/// Parses an RSS runtime feed, like the `Zeus-Runtime.rss`. Every `item` with an `enclosure`
+/// is a runtime. The version comes from the enclosure's `version` attribute, or else from the
+/// item's `title`. A `md5` or `sha256`, either as an enclosure attribute or as a child of the item,
+/// is used to verify the download.
+pub fn parse_feed(xml: &str) -> AnyResult<Vec<FeedEntry>> {
+    let document = roxmltree::Document::parse(xml)?;
+
+    let entries = document
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter_map(|item| {
+            let enclosure = item
+                .descendants()
+                .find(|node| node.has_tag_name("enclosure"))?;
+            let attribute = |name: &str| {
+                enclosure
+                    .attributes()
+                    .find(|attr| attr.name() == name)
+                    .map(|attr| attr.value().trim().to_owned())
+            };
+            let child_text = |name: &str| {
+                item.children()
+                    .find(|node| node.has_tag_name(name))
+                    .and_then(|node| node.text())
+                    .map(|txt| txt.trim().to_owned())
+            };
+
+            let version = attribute("version")
+                .and_then(|v| RuntimeVersion::parse(&v))
+                .or_else(|| {
+                    child_text("title")?
+                        .split(|c: char| c.is_whitespace() || c == '-')
+                        .find_map(RuntimeVersion::parse)
+                })?;
+
+            let checksum = attribute("sha256")
+                .or_else(|| child_text("sha256"))
+                .map(Checksum::Sha256)
+                .or_else(|| {
+                    attribute("md5")
+                        .or_else(|| child_text("md5"))
+                        .map(Checksum::Md5)
+                });
+
+            Some(FeedEntry {
+                version,
+                location: attribute("url")?,
+                length: attribute("length").and_then(|v| v.parse().ok()),
+                checksum,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Resolves where an entry's archive lives. Relative locations in a feed on disk are relative
+/// to that feed, which lets a mirror be a plain folder.
+pub fn entry_location(feed: &str, entry: &FeedEntry) -> String {
+    if is_remote(&entry.location) || is_remote(feed) {
+        return entry.location.clone();
+    }
+
+    let location = Utf8Path::new(&entry.location);
+    if location.is_absolute() {
+        entry.location.clone()
+    } else {
+        Utf8Path::new(feed)
+            .parent()
+            .map(|parent| parent.join(location).to_string())
+            .unwrap_or_else(|| entry.location.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feed() {
+        let txt = r#"<?xml version="1.0" encoding="utf-8"?>
+<rss version="2.0" xmlns:sparkle="http://www.andymatuschak.org/xml-namespaces/sparkle">
+  <channel>
+    <title>Runtime</title>
+    <item>
+      <title>Version 2024.6.0.205</title>
+      <enclosure url="https://example.com/runtime-2024.6.0.205.zip" sparkle:version="2024.6.0.205" length="1024" md5="abc123" />
+    </item>
+    <item>
+      <title>Version 2024.11.0.226</title>
+      <sha256>DEF456</sha256>
+      <enclosure url="runtime-2024.11.0.226.zip" />
+    </item>
+    <item>
+      <title>Release notes only</title>
+    </item>
+  </channel>
+</rss>"#;
+
+        let entries = parse_feed(txt).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].version.to_string(), "2024.6.0.205");
+        assert_eq!(entries[0].length, Some(1024));
+        assert_eq!(entries[0].checksum, Some(Checksum::Md5("abc123".into())));
+
+        assert_eq!(entries[1].version.to_string(), "2024.11.0.226");
+        assert_eq!(entries[1].checksum, Some(Checksum::Sha256("DEF456".into())));
+        assert_eq!(
+            entry_location("/mirror/feed.rss", &entries[1]),
+            "/mirror/runtime-2024.11.0.226.zip"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use color_eyre::{eyre::bail, Help};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use super::{entry_location, is_remote, read_feed, Checksum, FeedEntry, RuntimeVersion};
+use crate::AnyResult;
+
+/// Where a runtime archive comes from.
+pub enum RuntimeSource<'a> {
+    /// Look the version up in this feed, and download or copy it from there.
+    Feed(&'a str),
+    /// Use this archive on disk, checking it against the feed's entry for the version if the feed
+    /// has one.
+    Archive {
+        archive: &'a Utf8Path,
+        feed: &'a str,
+    },
+}
+
+/// Installs a runtime into `runtimes_directory/runtime-<version>`, returning the path it was installed to.
+pub fn install_runtime(
+    version: &RuntimeVersion,
+    source: RuntimeSource<'_>,
+    runtimes_directory: &Utf8Path,
+    force: bool,
+) -> AnyResult<Utf8PathBuf> {
+    let destination = runtimes_directory.join(format!("runtime-{}", version));
+    if destination.exists() && force == false {
+        bail!(
+            "runtime {} is already installed at `{}`. Pass `--force` to reinstall it",
+            version,
+            destination
+        );
+    }
+
+    std::fs::create_dir_all(runtimes_directory)
+        .with_note(|| format!("could not create `{}`", runtimes_directory))?;
+
+    let download_path = runtimes_directory.join(format!("runtime-{}.download", version));
+    let partial = runtimes_directory.join(format!("runtime-{}.partial", version));
+    let result = install(version, source, &download_path, &partial, &destination);
+
+    // not our business if this fails
+    if download_path.exists() {
+        let _ = std::fs::remove_file(&download_path);
+    }
+    if result.is_err() && partial.exists() {
+        let _ = std::fs::remove_dir_all(&partial);
+    }
+
+    result.map(|()| destination)
+}
+
+/// Fetches and verifies the archive, unpacks it into `partial`, and then moves that to the
+/// destination. The caller cleans up after a failure.
+fn install(
+    version: &RuntimeVersion,
+    source: RuntimeSource<'_>,
+    download_path: &Utf8Path,
+    partial: &Utf8Path,
+    destination: &Utf8Path,
+) -> AnyResult {
+    let archive = match source {
+        RuntimeSource::Archive { archive, feed } => {
+            match read_feed(feed) {
+                Ok(entries) => match entries.iter().find(|v| &v.version == version) {
+                    Some(entry) => verify(entry, archive)?,
+                    None => adam_print!(
+                        "{}: runtime {} is not in the feed `{}`, so `{}` was not verified",
+                        console::style("adam warning").bright().yellow(),
+                        version,
+                        feed,
+                        archive
+                    ),
+                },
+                Err(e) => adam_print!(
+                    "{}: could not read the feed `{}`, so `{}` was not verified: {:#}",
+                    console::style("adam warning").bright().yellow(),
+                    feed,
+                    archive,
+                    e
+                ),
+            }
+
+            archive
+        }
+        RuntimeSource::Feed(feed) => {
+            let entries = read_feed(feed)?;
+            let Some(entry) = entries.iter().find(|v| &v.version == version) else {
+                let mut available: Vec<_> = entries.iter().map(|v| &v.version).collect();
+                available.sort();
+                let available: Vec<_> = available
+                    .iter()
+                    .rev()
+                    .take(5)
+                    .map(|v| v.to_string())
+                    .collect();
+
+                bail!(
+                    "runtime {} is not in the feed `{}`. The newest available are: {}",
+                    version,
+                    feed,
+                    available.join(", ")
+                );
+            };
+
+            fetch(&entry_location(feed, entry), download_path)?;
+            verify(entry, download_path)?;
+
+            download_path
+        }
+    };
+
+    if partial.exists() {
+        std::fs::remove_dir_all(partial)?;
+    }
+    unpack(archive, partial).with_note(|| format!("could not unpack `{}`", archive))?;
+
+    if destination.exists() {
+        std::fs::remove_dir_all(destination)?;
+    }
+    std::fs::rename(partial, destination)?;
+
+    Ok(())
+}
+
+/// Downloads (or copies) the archive to the given path, with a progress bar.
+fn fetch(location: &str, output: &Utf8Path) -> AnyResult {
+    let (mut reader, length): (Box<dyn Read>, Option<u64>) = if is_remote(location) {
+        let response = ureq::get(location)
+            .call()
+            .with_note(|| format!("could not download `{}`", location))?;
+        let length = response
+            .header("Content-Length")
+            .and_then(|v| v.parse().ok());
+
+        (response.into_reader(), length)
+    } else {
+        let file = File::open(location).with_note(|| format!("could not read `{}`", location))?;
+        let length = file.metadata().ok().map(|v| v.len());
+
+        (Box::new(file), length)
+    };
+
+    let progress_bar = indicatif::ProgressBar::new(length.unwrap_or_default());
+    progress_bar.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template(
+                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes}",
+            )
+            .unwrap()
+            .progress_chars("#> "),
+    );
+    progress_bar.println(format!(
+        "{} {}",
+        console::style("Downloading").green().bright(),
+        location
+    ));
+
+    let mut file = File::create(output)?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+
+        file.write_all(&buffer[..read])?;
+        progress_bar.inc(read as u64);
+    }
+    progress_bar.finish_and_clear();
+
+    Ok(())
+}
+
+/// Checks the archive against the length and checksum in the feed.
+fn verify(entry: &FeedEntry, archive: &Utf8Path) -> AnyResult {
+    if let Some(length) = entry.length {
+        let actual = std::fs::metadata(archive)?.len();
+        if actual != length {
+            bail!(
+                "runtime archive was {} bytes, but the feed says it should be {} bytes",
+                actual,
+                length
+            );
+        }
+    }
+
+    let Some(checksum) = &entry.checksum else {
+        adam_print!(
+            "{}: the feed has no checksum for runtime {}, so it was not verified",
+            console::style("adam warning").bright().yellow(),
+            entry.version
+        );
+        return Ok(());
+    };
+
+    let mut file = File::open(archive)?;
+    let (expected, actual) = match checksum {
+        Checksum::Md5(expected) => {
+            let mut hasher = Md5::new();
+            std::io::copy(&mut file, &mut hasher)?;
+            (expected, hex(&hasher.finalize()))
+        }
+        Checksum::Sha256(expected) => {
+            let mut hasher = Sha256::new();
+            std::io::copy(&mut file, &mut hasher)?;
+            (expected, hex(&hasher.finalize()))
+        }
+    };
+
+    if expected.eq_ignore_ascii_case(&actual) == false {
+        bail!(
+            "checksum mismatch for runtime {}: expected `{}` but got `{}`",
+            entry.version,
+            expected,
+            actual
+        );
+    }
+
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|v| format!("{:02x}", v)).collect()
+}
+
+/// Unpacks a zip archive into the output folder. If every file in the archive is inside a single
+/// `runtime-*` folder, that folder is stripped.
+fn unpack(archive: &Utf8Path, output: &Utf8Path) -> AnyResult {
+    let mut zip = zip::ZipArchive::new(File::open(archive)?)?;
+
+    let prefix = {
+        let mut roots = zip.file_names().filter_map(|v| v.split('/').next());
+        let first = roots.next().unwrap_or_default().to_owned();
+
+        (first.starts_with("runtime-") && roots.all(|v| v == first)).then_some(first)
+    };
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i)?;
+        let Some(name) = entry.enclosed_name().map(|v| v.to_owned()) else {
+            bail!("archive contains an unsafe path `{}`", entry.name());
+        };
+        let name = match &prefix {
+            Some(prefix) => name.strip_prefix(prefix).unwrap_or(&name).to_owned(),
+            None => name,
+        };
+        let path = output.as_std_path().join(name);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&path)?;
+        std::io::copy(&mut entry, &mut file)?;
+
+        // igor and the runners need to stay executable
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::{process::ExitCode, time::Duration};
+
+use crate::{
+    runner::{self, Stage, TimingHistory, TIMED_STAGES},
+    RunOptions,
+};
+
+/// Shows how long each stage of the last builds took, for each configuration and VM/YYC of the
+/// target, and points out any stage which was slower than usual in the latest build.
+pub fn stats(last: usize, options: RunOptions) -> ExitCode {
+    let target = options.task.target;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map(|v| v.as_secs())
+        .unwrap_or_default();
+
+    let mut shown = 0;
+    for kind in ["vm", "yyc"] {
+        let cache = options
+            .task
+            .output_folder
+            .join(target.to_string())
+            .join(kind)
+            .join("cache");
+        let history = TimingHistory::load(&cache);
+
+        let mut configs: Vec<&str> = history.builds.iter().map(|v| v.config.as_str()).collect();
+        configs.sort_unstable();
+        configs.dedup();
+
+        for config in configs {
+            let builds: Vec<_> = history.builds_for(config, kind).collect();
+            let recent = &builds[builds.len().saturating_sub(last)..];
+            shown += 1;
+
+            println!(
+                "{} {} {} {}",
+                console::style("Build times").green().bright(),
+                target,
+                kind,
+                console::style(config).yellow().bright(),
+            );
+            println!(
+                "  {:<14}{}{:>10}",
+                "finished",
+                TIMED_STAGES
+                    .iter()
+                    .map(|v| format!("{:>15}", stage_name(*v)))
+                    .collect::<String>(),
+                "total"
+            );
+
+            for build in recent {
+                print_row(&age(build.finished, now), &build.stages);
+            }
+            let average = runner::StageTimes::average(builds.iter().map(|v| &v.stages));
+            print_row(&format!("average of {}", builds.len()), &average);
+
+            for regression in runner::regressions(&builds) {
+                println!(
+                    "  {}: {} took {} in the last build, but usually takes {}",
+                    console::style("regression").yellow().bright(),
+                    stage_name(regression.stage),
+                    seconds(regression.latest),
+                    seconds(regression.average),
+                );
+            }
+            println!();
+        }
+    }
+
+    if shown == 0 {
+        println!(
+            "no build timings for {} yet. adam records them in `{}` after each build",
+            target,
+            options.task.output_folder.join(target.to_string()),
+        );
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn print_row(label: &str, stages: &runner::StageTimes) {
+    println!(
+        "  {:<14}{}{:>10}",
+        label,
+        TIMED_STAGES
+            .iter()
+            .map(|v| format!(
+                "{:>15}",
+                stages
+                    .get(*v)
+                    .map(seconds)
+                    .unwrap_or_else(|| "-".to_owned())
+            ))
+            .collect::<String>(),
+        seconds(stages.total())
+    );
+}
+
+fn stage_name(stage: Stage) -> &'static str {
+    match stage {
+        Stage::Initialize => "initialize",
+        Stage::Compile => "compile",
+        Stage::ChunkBuilder => "chunk builder",
+        Stage::PreRun => "pre-run",
+        Stage::Run => "run",
+    }
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+/// How long ago a build finished, such as `3h ago`.
+fn age(finished: u64, now: u64) -> String {
+    let secs = now.saturating_sub(finished);
+
+    match secs {
+        0..=59 => "just now".to_owned(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
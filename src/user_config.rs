@@ -0,0 +1,181 @@
+use std::process::ExitCode;
+
+use color_eyre::eyre::{bail, eyre, WrapErr};
+
+use crate::{
+    input::{self, ManifestKey, UserConfigOptions, ValueKind},
+    AnyResult,
+};
+
+type Values = serde_json::Map<String, serde_json::Value>;
+
+pub fn user_config_request(request: UserConfigOptions) -> ExitCode {
+    let result = match request {
+        UserConfigOptions::View => load().map(|config| {
+            println!("{}", toml::to_string_pretty(&config).unwrap());
+        }),
+        UserConfigOptions::Path => config_path().map(|path| println!("{}", path)),
+        UserConfigOptions::Get { key } => get(&key),
+        UserConfigOptions::Set { key, value } => edit(&key, |key, values| {
+            let value = key.kind.parse(&value).ok_or_else(|| {
+                eyre!(
+                    "`{}` takes a {}, but `{}` is not a {}",
+                    key.name,
+                    key.kind,
+                    value,
+                    key.kind
+                )
+            })?;
+            values.insert(key.name.clone(), value);
+
+            Ok(())
+        }),
+        UserConfigOptions::Unset { key } => edit(&key, |key, values| {
+            values.remove(&key.name);
+
+            Ok(())
+        }),
+        UserConfigOptions::Add { key, value } => edit(&key, |key, values| {
+            let list = list_mut(key, values)?;
+            if list.iter().any(|v| v.as_str() == Some(value.as_str())) == false {
+                list.push(value.into());
+            }
+
+            Ok(())
+        }),
+        UserConfigOptions::Remove { key, value } => edit(&key, |key, values| {
+            let list = list_mut(key, values)?;
+            let Some(position) = list.iter().position(|v| v.as_str() == Some(value.as_str()))
+            else {
+                bail!("`{}` does not contain `{}`", key.name, value);
+            };
+            list.remove(position);
+
+            Ok(())
+        }),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            println!("{}: {:#}", console::style("adam error").bright().red(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn get(key: &str) -> AnyResult<()> {
+    let key = find_key(key)?;
+    let values = to_values(&load()?);
+
+    match values.get(&key.name) {
+        None | Some(serde_json::Value::Null) => {
+            bail!("`{}` is not set in the user config", key.name)
+        }
+        Some(serde_json::Value::String(v)) => println!("{}", v),
+        // lists are printed the same way `set` takes them
+        Some(serde_json::Value::Array(list)) => {
+            let list: Vec<_> = list.iter().filter_map(|v| v.as_str()).collect();
+            println!("{}", list.join(","));
+        }
+        Some(v) => println!("{}", v),
+    }
+
+    Ok(())
+}
+
+/// Changes the values of the user config, checks that they still make a valid config, and
+/// saves it.
+fn edit(
+    key: &str,
+    change: impl FnOnce(&ManifestKey, &mut Values) -> AnyResult<()>,
+) -> AnyResult<()> {
+    let key = find_key(key)?;
+    let mut values = to_values(&load()?);
+
+    change(&key, &mut values)?;
+
+    let config: input::Manifest = serde_json::from_value(serde_json::Value::Object(values))
+        .wrap_err_with(|| format!("invalid value for `{}`", key.name))?;
+
+    if config.configuration.name.is_some() && config.configuration.overrides.is_empty() == false {
+        bail!(
+            "the user config has `[configuration.<Name>]` tables, so it cannot also set `configuration`. Use `-c` or a profile instead."
+        );
+    }
+
+    confy::store("adam", None, config).wrap_err("could not save the user config")?;
+    println!(
+        "{}: user configuration has been saved.",
+        console::style("success").green().bright(),
+    );
+
+    Ok(())
+}
+
+/// Finds a key which can be edited from the command line.
+fn find_key(name: &str) -> AnyResult<ManifestKey> {
+    let keys = input::manifest_keys();
+    let Some(key) = keys.iter().find(|v| v.name == name) else {
+        bail!(
+            "unknown key `{}`. Keys are: {}",
+            name,
+            input::describe_keys(&keys)
+        );
+    };
+
+    if key.deprecated {
+        bail!("`{}` is deprecated", key.name);
+    }
+    if key.kind == ValueKind::Table {
+        bail!(
+            "`{}` is a table, so it can only be edited in the user config file at `{}`",
+            key.name,
+            config_path()?
+        );
+    }
+
+    Ok(key.clone())
+}
+
+/// Returns the list at the key, making an empty one if it isn't set.
+fn list_mut<'a>(
+    key: &ManifestKey,
+    values: &'a mut Values,
+) -> AnyResult<&'a mut Vec<serde_json::Value>> {
+    if key.kind != ValueKind::List {
+        bail!(
+            "`{}` is a {}, not a list. Use `adam user-config set` instead",
+            key.name,
+            key.kind
+        );
+    }
+
+    let value = values
+        .entry(key.name.clone())
+        .or_insert_with(|| serde_json::Value::Array(vec![]));
+    if value.is_null() {
+        *value = serde_json::Value::Array(vec![]);
+    }
+
+    Ok(value.as_array_mut().unwrap())
+}
+
+fn load() -> AnyResult<input::Manifest> {
+    confy::load("adam", None).wrap_err("could not read the user config")
+}
+
+fn config_path() -> AnyResult<String> {
+    let path = confy::get_configuration_file_path("adam", None)
+        .wrap_err("could not find the user config")?;
+
+    Ok(path.display().to_string())
+}
+
+fn to_values(config: &input::Manifest) -> Values {
+    let serde_json::Value::Object(values) = serde_json::to_value(config).unwrap() else {
+        unreachable!("a manifest always serializes to a map");
+    };
+
+    values
+}
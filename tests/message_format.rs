@@ -0,0 +1,82 @@
+//! Runs adam against a fake runtime, whose Igor is a shell script which prints what the real one
+//! does, to check what a whole build writes to stdout.
+#![cfg(target_os = "linux")]
+
+use std::{os::unix::fs::PermissionsExt, path::Path, process::Command};
+
+const IGOR: &str = r#"#!/bin/sh
+echo "Loading project"
+echo "[Compile] Run GML Compiler"
+echo "Warning : gml_Script_scr_move(3) : GM2016: unused variable"
+echo "Final Compile...finished"
+echo "Finished PrepareGame()"
+echo "Run_Start"
+echo "Entering main loop."
+echo "INFO: hello from the game"
+echo "Igor complete."
+"#;
+
+fn write(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn json_mode_only_writes_json_to_stdout() {
+    let dir = std::env::temp_dir().join(format!("adam-json-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let igor = dir.join("runtime/bin/igor/linux/x64/Igor");
+    write(&igor, IGOR);
+    std::fs::set_permissions(&igor, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::create_dir_all(dir.join("license")).unwrap();
+    std::fs::create_dir_all(dir.join("gamemaker")).unwrap();
+
+    let project = dir.join("project");
+    write(
+        &project.join("Game.yyp"),
+        r#"{"configs":{"name":"Default","children":[]},"MetaData":{"IDEVersion":"2024.6.0.205"}}"#,
+    );
+    // the check script's output is plain text, so it has to go to stderr too
+    let check = project.join("check.sh");
+    write(&check, "#!/bin/sh\necho checking the project\n");
+    std::fs::set_permissions(&check, std::fs::Permissions::from_mode(0o755)).unwrap();
+    write(
+        &project.join(".adam.toml"),
+        r#"path_to_run_nix = "check.sh""#,
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_adam"))
+        .args(["run", "--message-format", "json", "-v"])
+        .arg("--no-user-folder")
+        .arg("--user-license-folder")
+        .arg(dir.join("license"))
+        .arg("--runtime-location-override")
+        .arg(dir.join("runtime"))
+        .arg("--gms2-install-location")
+        .arg(dir.join("gamemaker"))
+        .current_dir(&project)
+        // keep the developer's own config out of it
+        .env("HOME", &dir)
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{}\n{}", stdout, stderr);
+    assert!(stderr.contains("checking the project"), "{}", stderr);
+
+    let reasons: Vec<String> = stdout
+        .lines()
+        .map(|line| {
+            let event: serde_json::Value = serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("`{}` is not json: {}", line, e));
+            event["reason"].as_str().unwrap().to_owned()
+        })
+        .collect();
+    assert_eq!(reasons.first().map(String::as_str), Some("build-started"));
+    assert_eq!(reasons.last().map(String::as_str), Some("build-finished"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}